@@ -0,0 +1,363 @@
+// How many colours the terminal can show, and the nearest colours it has for the ones we'd
+// like. The Linux console and many serial consoles only know the 16 ANSI colours, and
+// `NO_COLOR` (https://no-color.org) asks for none at all.
+
+use crate::sys::AnsiColor;
+use std::{env, fs, path::PathBuf};
+
+/// Where terminfo databases usually live, after `$TERMINFO`, `~/.terminfo` and
+/// `$TERMINFO_DIRS`.
+const TERMINFO_DIRS: [&str; 3] = ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"];
+
+/// Index of the `colors` capability among the numbers of a compiled terminfo entry.
+const TERMINFO_COLORS: usize = 13;
+
+/// Levels of each channel in the 6x6x6 cube of the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Squared distance below which two colours are hard to tell apart at a glance.
+const CLOSE_DISTANCE: u32 = 80 * 80;
+
+/// xterm's defaults for the 16 ANSI colours.
+const ANSI_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    NoColor,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// The depth of the terminal we're running in.
+    pub fn detect() -> ColorDepth {
+        ColorDepth::from_env(|name| env::var(name).ok(), terminfo_colors)
+    }
+
+    /// `NO_COLOR` wins, then `COLORTERM`, then the `colors` the terminfo entry for `TERM`
+    /// gives, then a guess from the name of `TERM`.
+    pub fn from_env<F, T>(var: F, terminfo: T) -> ColorDepth
+    where
+        F: Fn(&str) -> Option<String>,
+        T: Fn(&str) -> Option<i32>,
+    {
+        if var("NO_COLOR").filter(|value| !value.is_empty()).is_some() {
+            return ColorDepth::NoColor;
+        }
+        match var("COLORTERM").as_deref() {
+            Some("truecolor") | Some("24bit") => return ColorDepth::TrueColor,
+            _ => {}
+        }
+        let term = var("TERM").unwrap_or_default();
+        if term.is_empty() || term == "dumb" {
+            return ColorDepth::NoColor;
+        }
+        if term.ends_with("-direct") || term.ends_with("truecolor") {
+            return ColorDepth::TrueColor;
+        }
+        match terminfo(&term) {
+            Some(colors) if colors >= 1 << 24 => ColorDepth::TrueColor,
+            Some(colors) if colors >= 256 => ColorDepth::Ansi256,
+            Some(colors) if colors >= 8 => ColorDepth::Ansi16,
+            Some(_) => ColorDepth::NoColor,
+            None if term.ends_with("256color") => ColorDepth::Ansi256,
+            None => ColorDepth::Ansi16,
+        }
+    }
+
+    /// The nearest colour the terminal has, or `None` when it shows no colours.
+    pub fn apply(self, color: AnsiColor) -> Option<AnsiColor> {
+        match (self, color) {
+            (ColorDepth::NoColor, _) => None,
+            (ColorDepth::TrueColor, color) => Some(color),
+            (ColorDepth::Ansi256, AnsiColor::Rgb(r, g, b)) => {
+                Some(AnsiColor::Indexed(nearest_256(r, g, b)))
+            }
+            (ColorDepth::Ansi256, color) => Some(color),
+            (ColorDepth::Ansi16, AnsiColor::Indexed(index)) if index < 16 => Some(color),
+            (ColorDepth::Ansi16, color) => {
+                let (r, g, b) = rgb(color);
+                Some(AnsiColor::Indexed(nearest_16(r, g, b)))
+            }
+        }
+    }
+}
+
+/// The colour of a palette entry, with xterm's defaults for the first 16.
+pub fn palette(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_16[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn rgb(color: AnsiColor) -> (u8, u8, u8) {
+    match color {
+        AnsiColor::Indexed(index) => palette(index),
+        AnsiColor::Rgb(r, g, b) => (r, g, b),
+    }
+}
+
+/// Whether `a` and `b` look alike, palette entries judged by xterm's defaults. An accent
+/// like that of a status colour would make healthy things look like warnings.
+pub fn is_close(a: AnsiColor, b: AnsiColor) -> bool {
+    distance(rgb(a), rgb(b)) < CLOSE_DISTANCE
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    channel(r1, r2) + channel(g1, g2) + channel(b1, b2)
+}
+
+/// The closest colour of the 6x6x6 cube or the grey ramp of the 256-colour palette. The
+/// first 16 are left out since terminals are free to redefine them.
+pub fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (i32::from(CUBE_LEVELS[i]) - i32::from(channel)).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+    if distance(palette(grey), (r, g, b)) < distance(palette(cube), (r, g, b)) {
+        grey
+    } else {
+        cube
+    }
+}
+
+/// The closest of the 16 ANSI colours.
+pub fn nearest_16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16)
+        .min_by_key(|&index| distance(palette(index), (r, g, b)))
+        .unwrap_or(7)
+}
+
+/// The `colors` capability of the compiled terminfo entry for `term`. `Some(0)` when the
+/// entry exists but has no colours, `None` when there's no entry.
+pub fn terminfo_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(dir.into());
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(terminfo_dirs) = env::var("TERMINFO_DIRS") {
+        dirs.extend(
+            terminfo_dirs
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+    dirs.extend(TERMINFO_DIRS.iter().map(PathBuf::from));
+    dirs.iter()
+        .flat_map(|dir| {
+            // macOS names the subdirectories by the hex of the first letter.
+            [
+                dir.join(first.to_string()).join(term),
+                dir.join(format!("{:x}", first as u32)).join(term),
+            ]
+        })
+        .find_map(|path| fs::read(path).ok())
+        .and_then(|entry| parse_terminfo_colors(&entry))
+}
+
+/// Reads `colors` from a compiled terminfo entry, in either the legacy format with 16-bit
+/// numbers or the extended one with 32-bit numbers.
+pub fn parse_terminfo_colors(entry: &[u8]) -> Option<i32> {
+    let header = |i: usize| -> Option<usize> {
+        let bytes = entry.get(i * 2..i * 2 + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let number_size = match header(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let (names, booleans, numbers) = (header(1)?, header(2)?, header(3)?);
+    if numbers <= TERMINFO_COLORS {
+        return Some(0);
+    }
+    // Numbers start on an even byte.
+    let start = 12 + names + booleans;
+    let start = start + start % 2 + TERMINFO_COLORS * number_size;
+    let bytes = entry.get(start..start + number_size)?;
+    let colors = if number_size == 2 {
+        i32::from(i16::from_le_bytes([bytes[0], bytes[1]]))
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+    Some(colors.max(0))
+}
+
+/// A colour for `termion::color::Fg` and `Bg` that comes out as deep as the terminal goes,
+/// using the basic SGR codes for the 16 ANSI colours, and nothing at all for `NoColor`.
+/// `None` is the terminal's default colour.
+#[cfg(feature = "terminal")]
+#[derive(Clone, Copy, Debug)]
+pub struct Paint {
+    pub color: Option<AnsiColor>,
+    pub depth: ColorDepth,
+}
+
+#[cfg(feature = "terminal")]
+impl Paint {
+    pub fn new(color: Option<AnsiColor>, depth: ColorDepth) -> Paint {
+        Paint { color, depth }
+    }
+
+    /// A palette colour by number, the first 16 being the ANSI ones.
+    pub fn ansi(index: u8, depth: ColorDepth) -> Paint {
+        Paint::new(Some(AnsiColor::Indexed(index)), depth)
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter, base: u8) -> std::fmt::Result {
+        if self.depth == ColorDepth::NoColor {
+            return Ok(());
+        }
+        let bright = base + 60;
+        match self.color.and_then(|color| self.depth.apply(color)) {
+            None => write!(f, "\x1b[{}m", base + 9),
+            Some(AnsiColor::Indexed(index)) if index < 8 => write!(f, "\x1b[{}m", base + index),
+            Some(AnsiColor::Indexed(index)) if index < 16 => {
+                write!(f, "\x1b[{}m", bright + index - 8)
+            }
+            Some(AnsiColor::Indexed(index)) => write!(f, "\x1b[{};5;{}m", base + 8, index),
+            Some(AnsiColor::Rgb(r, g, b)) => write!(f, "\x1b[{};2;{};{};{}m", base + 8, r, g, b),
+        }
+    }
+}
+
+#[cfg(feature = "terminal")]
+impl termion::color::Color for Paint {
+    fn write_fg(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.write(f, 30)
+    }
+
+    fn write_bg(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.write(f, 40)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_color_depth() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let terminfo = |term: &str| match term {
+            "xterm-256color" => Some(256),
+            "linux" => Some(8),
+            "vt100" => Some(0),
+            _ => None,
+        };
+        let depth = |vars| ColorDepth::from_env(env(vars), terminfo);
+        assert_eq!(depth(&[("TERM", "linux")]), ColorDepth::Ansi16);
+        assert_eq!(depth(&[("TERM", "xterm-256color")]), ColorDepth::Ansi256);
+        assert_eq!(depth(&[("TERM", "vt100")]), ColorDepth::NoColor);
+        assert_eq!(depth(&[("TERM", "dumb")]), ColorDepth::NoColor);
+        assert_eq!(depth(&[("TERM", "screen-256color")]), ColorDepth::Ansi256);
+        assert_eq!(
+            depth(&[("TERM", "linux"), ("COLORTERM", "truecolor")]),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            depth(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]),
+            ColorDepth::NoColor
+        );
+        assert_eq!(
+            depth(&[("TERM", "xterm-256color"), ("NO_COLOR", "")]),
+            ColorDepth::Ansi256
+        );
+    }
+
+    #[test]
+    fn test_nearest_color() {
+        assert_eq!(nearest_256(255, 135, 0), 208);
+        assert_eq!(nearest_256(128, 128, 128), 244);
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_16(250, 10, 10), 9);
+        assert_eq!(nearest_16(200, 200, 200), 7);
+        assert_eq!(nearest_16(41, 43, 47), 0);
+        for index in 16..=255 {
+            let (r, g, b) = palette(index);
+            assert_eq!(palette(nearest_256(r, g, b)), (r, g, b), "{}", index);
+        }
+
+        let rgb = AnsiColor::Rgb(93, 77, 122);
+        assert_eq!(ColorDepth::TrueColor.apply(rgb), Some(rgb));
+        assert_eq!(ColorDepth::Ansi256.apply(rgb), Some(AnsiColor::Indexed(60)));
+        assert_eq!(ColorDepth::Ansi16.apply(rgb), Some(AnsiColor::Indexed(8)));
+        assert_eq!(
+            ColorDepth::Ansi16.apply(AnsiColor::Indexed(4)),
+            Some(AnsiColor::Indexed(4))
+        );
+        assert_eq!(ColorDepth::NoColor.apply(rgb), None);
+
+        assert!(is_close(AnsiColor::Indexed(9), AnsiColor::Indexed(1)));
+        assert!(is_close(AnsiColor::Rgb(220, 30, 30), AnsiColor::Indexed(1)));
+        assert!(!is_close(
+            AnsiColor::Rgb(23, 147, 209),
+            AnsiColor::Indexed(1)
+        ));
+    }
+
+    #[test]
+    fn test_terminfo_colors() {
+        // Legacy format: names "x|y\0", two booleans, 14 numbers with colors at 13.
+        let mut entry = Vec::new();
+        for value in &[0o432u16, 4, 2, 14, 0, 0] {
+            entry.extend_from_slice(&value.to_le_bytes());
+        }
+        entry.extend_from_slice(b"x|y\0");
+        entry.extend_from_slice(&[1, 0]);
+        for i in 0..14 {
+            let value: i16 = if i == 13 { 256 } else { -1 };
+            entry.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(parse_terminfo_colors(&entry), Some(256));
+        entry[0] = 0;
+        assert_eq!(parse_terminfo_colors(&entry), None);
+        assert_eq!(parse_terminfo_colors(&[]), None);
+    }
+}
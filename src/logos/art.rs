@@ -0,0 +1,233 @@
+// Small ASCII logos, mostly after neofetch's `--ascii_distro <name>_small`. `$1` to `$9`
+// switch to the logo's first to ninth colour, which are ANSI colour numbers.
+
+use super::Logo;
+
+pub const TUX: Logo = Logo {
+    ids: &["linux"],
+    colors: &[7, 3],
+    art: r#"$1    ___
+   (.. |
+   ($2<>$1 |
+  / __  \
+ ( /  \ /|
+$2_/\ __)/_)
+\/-____\/"#,
+};
+
+pub const LOGOS: &[Logo] = &[
+    Logo {
+        ids: &["alpine"],
+        colors: &[4],
+        art: r#"$1   /\ /\
+  // \  \
+ //   \  \
+///    \  \
+//      \  \
+         \"#,
+    },
+    Logo {
+        ids: &["arch", "archarm", "archlinux"],
+        colors: &[6],
+        art: r#"$1      /\
+     /  \
+    /\   \
+   /      \
+  /   ,,   \
+ /   |  |  -\
+/_-''    ''-_\"#,
+    },
+    Logo {
+        ids: &["bedrock"],
+        colors: &[8, 7],
+        art: r#"$1__
+\ \___
+ \  _ \
+  \___/"#,
+    },
+    Logo {
+        ids: &["centos"],
+        colors: &[3, 2, 4, 5],
+        art: r#"$2 ____$1^$4____
+ $2|\  $1|  $4/|
+ $2| \ $1| $4/ |
+$4<---- $3---->
+ $3| / $2| $1\ |
+ $3|/__$2|__$1\|
+     $2v"#,
+    },
+    Logo {
+        ids: &["debian"],
+        colors: &[1, 7],
+        art: r#"$1  _____
+ /  __ \
+|  /    |
+|  \___-
+-_
+  --_"#,
+    },
+    Logo {
+        ids: &["elementary"],
+        colors: &[7],
+        art: r#"$1  _______
+ / ____  \
+/  |  /  /\
+|__\ /  / |
+\   /__/  /
+ \_______/"#,
+    },
+    Logo {
+        ids: &["fedora"],
+        colors: &[4, 7],
+        art: r#"$1      _____
+     /   __)$2\$1
+     |  /  $2\ \$1
+  ___|  |__$2/ /$1
+ / (_    _)$2_/$1
+/ /  |  |
+\ \__/  |
+ \(_____/"#,
+    },
+    Logo {
+        ids: &["gentoo"],
+        colors: &[5, 7],
+        art: r#"$1 _-----_
+(       \
+\    $20$1   \
+$2 \        )
+ /      _/
+(     _-
+\____-"#,
+    },
+    Logo {
+        ids: &["gobolinux"],
+        colors: &[5, 4],
+        art: r#"$1  _____       _
+ / ____|     | |
+| |  __  ___ | |__   ___
+| | |_ |/ _ \| '_ \ / _ \
+| |__| | (_) | |_) | (_) |
+ \_____|\___/|_.__/ \___/"#,
+    },
+    Logo {
+        ids: &["linuxmint", "mint"],
+        colors: &[2, 7],
+        art: r#"$1 ___________
+|_          \
+  | $2| _____$1 |
+  | $2| | | |$1 |
+  | $2| | | |$1 |
+  | $2\_____/$1 |
+  \_________/"#,
+    },
+    Logo {
+        ids: &["manjaro"],
+        colors: &[2],
+        art: r#"$1||||||||| ||||
+||||||||| ||||
+||||      ||||
+|||| |||| ||||
+|||| |||| ||||
+|||| |||| ||||
+|||| |||| ||||"#,
+    },
+    Logo {
+        ids: &["nixos"],
+        colors: &[4, 6],
+        art: r#"$1  \\  \\ //
+ ==\\__\\/ //
+   //   \\//
+==//     //==
+ //\\___//
+// /\\  \\==
+  // \\  \\"#,
+    },
+    Logo {
+        ids: &[
+            "opensuse",
+            "opensuse-leap",
+            "opensuse-tumbleweed",
+            "suse",
+            "sles",
+        ],
+        colors: &[2, 7],
+        art: r#"$1  _______
+__|   __ \
+     / $2.$1\ \
+     \__/ |
+   _______|
+   \_______
+__________/"#,
+    },
+    Logo {
+        ids: &["pop"],
+        colors: &[6, 7],
+        art: r#"$1______
+\   _ \        __
+ \ \ \ \      / /
+  \ \_\ \    / /
+   \  ___\  /_/
+    \ \    _
+   __\_\__(_)_
+  (___________)"#,
+    },
+    Logo {
+        ids: &["raspbian"],
+        colors: &[2, 1],
+        art: r#"$1   .~~.   .~~.
+  '. \ ' ' / .'
+$2   .~ .~~~..~.
+  : .~.'~'.~. :
+ ~ (   ) (   ) ~
+( : '~'.~.'~' : )
+ ~ .~       ~. ~
+  (  |   |   )
+  '~         ~'
+    '~-.~-'~"#,
+    },
+    Logo {
+        ids: &["rhel", "redhat"],
+        colors: &[1],
+        art: r#"$1      .M.:MMM
+     MMMMMMMMMM.
+    ,MMMMMMMMMMM
+ .MM MMMMMMMMMMM
+MMMM   MMMMMMMMM
+MMMMMM       MM
+ MMMMMMMMM,,,MMM
+   MMMMMMMMMMMMM
+      MMMMMMM"#,
+    },
+    Logo {
+        ids: &["siduction"],
+        colors: &[4, 7],
+        art: r#"$1      .-----.
+    .'  ___  '.
+   /  .'   '.  \
+  |  |  $2sid$1  |  |
+   \  '.___.'  /
+    '.       .'
+      '-----'"#,
+    },
+    Logo {
+        ids: &["ubuntu"],
+        colors: &[1, 7],
+        art: r#"$1         _
+     ---(_)
+ _/  ---  \
+(_) |   |
+  \  --- _/
+     ---(_)"#,
+    },
+    Logo {
+        ids: &["void"],
+        colors: &[2, 8],
+        art: r#"$1    _______
+ _ \______ -
+| \  $2___$1  \ |
+| | $2/   \$1 | |
+| | $2\___/$1 | |
+| \______ \_|
+ -_______\"#,
+    },
+];
@@ -0,0 +1,262 @@
+// Image logos for terminals with a graphics protocol. Kitty and iTerm2 take the PNG as is;
+// sixel needs it converted, which is left to `img2sixel` from libsixel when installed.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    os::unix::io::AsRawFd,
+    path::PathBuf,
+    process::Command,
+    time::{Duration, Instant},
+};
+use termion::raw::IntoRawMode;
+
+/// Where the image logo is looked for unless configured otherwise.
+pub const DEFAULT_IMAGE: &str = "/etc/nmotd/logo.png";
+
+/// How long to wait for the terminal to answer the capability queries.
+pub const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// The first bytes of every PNG file.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Kitty wants the payload split in chunks of at most this many base64 bytes.
+const KITTY_CHUNK: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+}
+
+/// A PNG to show instead of the ASCII logo, scaled to `columns` by `rows` cells.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageLogo {
+    pub path: PathBuf,
+    pub columns: u16,
+    pub rows: u16,
+}
+
+impl Default for ImageLogo {
+    fn default() -> ImageLogo {
+        ImageLogo {
+            path: PathBuf::from(DEFAULT_IMAGE),
+            columns: 24,
+            rows: 10,
+        }
+    }
+}
+
+/// An image ready to be written at the cursor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodedImage {
+    pub columns: u16,
+    pub rows: u16,
+    pub data: Vec<u8>,
+}
+
+impl ImageLogo {
+    /// Encodes the image for this terminal, or `None` when it can't show images or the
+    /// file isn't a readable PNG. Must run before the TUI takes over the terminal.
+    pub fn encode(&self) -> Option<EncodedImage> {
+        let png = fs::read(&self.path).ok().filter(|data| is_png(data))?;
+        let protocol = detect_protocol(QUERY_TIMEOUT)?;
+        let data = match protocol {
+            Protocol::Kitty => kitty(&png, self.columns, self.rows),
+            Protocol::Iterm2 => iterm2(&png, self.columns, self.rows),
+            Protocol::Sixel => self.sixel()?,
+        };
+        Some(EncodedImage {
+            columns: self.columns,
+            rows: self.rows,
+            data,
+        })
+    }
+
+    fn sixel(&self) -> Option<Vec<u8>> {
+        let (cell_width, cell_height) = cell_size();
+        let output = Command::new("img2sixel")
+            .arg("-w")
+            .arg((u32::from(self.columns) * cell_width).to_string())
+            .arg("-h")
+            .arg((u32::from(self.rows) * cell_height).to_string())
+            .arg(&self.path)
+            .output()
+            .ok()?;
+        if output.status.success() && !output.stdout.is_empty() {
+            Some(output.stdout)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(PNG_SIGNATURE)
+}
+
+/// Picks the protocol from the environment when the terminal is known to support one,
+/// otherwise asks the terminal. Kitty answers its graphics query, and sixel terminals list
+/// attribute 4 in their primary device attributes.
+pub fn detect_protocol(timeout: Duration) -> Option<Protocol> {
+    if let Some(protocol) = protocol_from_env(|name| env::var(name).ok()) {
+        return Some(protocol);
+    }
+    let is_tty = unsafe { libc::isatty(0) == 1 && libc::isatty(1) == 1 };
+    if !is_tty {
+        return None;
+    }
+    let response = query("\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\\x1b[c", timeout).ok()?;
+    protocol_from_response(&response)
+}
+
+pub fn protocol_from_env<F>(var: F) -> Option<Protocol>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let term = var("TERM").unwrap_or_default();
+    let program = var("TERM_PROGRAM").unwrap_or_default();
+    if term == "xterm-kitty" || var("KITTY_WINDOW_ID").is_some() {
+        Some(Protocol::Kitty)
+    } else if program == "iTerm.app"
+        || program == "WezTerm"
+        || var("LC_TERMINAL").as_deref() == Some("iTerm2")
+    {
+        Some(Protocol::Iterm2)
+    } else {
+        None
+    }
+}
+
+/// Reads the answers to the kitty graphics query and the device attributes request.
+pub fn protocol_from_response(response: &str) -> Option<Protocol> {
+    if response.contains("\x1b_Gi=31;OK") {
+        return Some(Protocol::Kitty);
+    }
+    let attributes = &response[response.find("\x1b[?")? + 3..];
+    let attributes = &attributes[..attributes.find('c')?];
+    if attributes.split(';').any(|attribute| attribute == "4") {
+        Some(Protocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// Writes `request` and collects the reply until the device attributes report, which
+/// every terminal sends, has arrived or `timeout` ran out. Whatever is left in stdin then
+/// is dropped, so a late reply doesn't reach the TUI as key presses.
+fn query(request: &str, timeout: Duration) -> io::Result<String> {
+    let mut stdout = io::stdout().into_raw_mode()?;
+    stdout.write_all(request.as_bytes())?;
+    stdout.flush()?;
+    let fd = io::stdin().as_raw_fd();
+    let response = read_reply(fd, timeout);
+    unsafe { libc::tcflush(fd, libc::TCIFLUSH) };
+    response
+}
+
+/// Reads `fd` directly, as bytes buffered by `io::stdin` would escape the flush.
+fn read_reply(fd: libc::c_int, timeout: Duration) -> io::Result<String> {
+    let deadline = Instant::now() + timeout;
+    let mut response = Vec::new();
+    let mut buf = [0; 256];
+    while !has_device_attributes(&response) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::default() {
+            break;
+        }
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        let read = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        if read < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if read == 0 {
+            break;
+        }
+        response.extend_from_slice(&buf[..read as usize]);
+    }
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+fn has_device_attributes(response: &[u8]) -> bool {
+    let response = String::from_utf8_lossy(response);
+    match response.find("\x1b[?") {
+        Some(start) => response[start..].contains('c'),
+        None => false,
+    }
+}
+
+/// Pixel size of a cell from the window size the kernel knows, with a common default
+/// for terminals that don't report pixels.
+fn cell_size() -> (u32, u32) {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::ioctl(1, libc::TIOCGWINSZ, &mut size) };
+    if ret == 0 && size.ws_xpixel > 0 && size.ws_col > 0 && size.ws_row > 0 {
+        (
+            u32::from(size.ws_xpixel / size.ws_col),
+            u32::from(size.ws_ypixel / size.ws_row),
+        )
+    } else {
+        (10, 20)
+    }
+}
+
+/// Kitty graphics protocol: transmit and display a PNG in chunks, quietly.
+pub fn kitty(png: &[u8], columns: u16, rows: u16) -> Vec<u8> {
+    let payload = base64(png);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut data = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            data.extend_from_slice(
+                format!("\x1b_Gf=100,a=T,q=2,c={},r={},m={};", columns, rows, more).as_bytes(),
+            );
+        } else {
+            data.extend_from_slice(format!("\x1b_Gm={};", more).as_bytes());
+        }
+        data.extend_from_slice(chunk);
+        data.extend_from_slice(b"\x1b\\");
+    }
+    data
+}
+
+/// iTerm2 inline images, also understood by WezTerm.
+pub fn iterm2(png: &[u8], columns: u16, rows: u16) -> Vec<u8> {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        columns,
+        rows,
+        base64(png)
+    )
+    .into_bytes()
+}
+
+pub fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | u32::from(*byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,217 @@
+// Distro logos shown beside the system info, picked from os-release like neofetch does.
+
+pub mod art;
+pub mod image;
+
+use self::image::{EncodedImage, ImageLogo};
+use crate::sys::OsRelease;
+use std::path::Path;
+
+/// What goes in the logo spot: the distro's ASCII art, or an image the terminal draws.
+pub enum ShownLogo {
+    Ascii(&'static Logo),
+    Image(EncodedImage),
+}
+
+impl ShownLogo {
+    /// The configured image when it exists and the terminal can show it, else the distro's
+    /// ASCII logo.
+    pub fn pick(image: Option<&ImageLogo>) -> ShownLogo {
+        match image
+            .filter(|image| image.path.is_file())
+            .and_then(ImageLogo::encode)
+        {
+            Some(encoded) => ShownLogo::Image(encoded),
+            None => ShownLogo::Ascii(detect()),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            ShownLogo::Ascii(logo) => logo.width(),
+            ShownLogo::Image(image) => image.columns as usize,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            ShownLogo::Ascii(logo) => logo.height(),
+            ShownLogo::Image(image) => image.rows as usize,
+        }
+    }
+}
+
+pub struct Logo {
+    /// os-release `ID`s this logo is for.
+    pub ids: &'static [&'static str],
+    /// ANSI colour numbers, selected in `art` with `$1`, `$2` and so on.
+    pub colors: &'static [u8],
+    pub art: &'static str,
+}
+
+impl Logo {
+    /// No art at all, for the frame an image logo is drawn into.
+    pub const BLANK: Logo = Logo {
+        ids: &[],
+        colors: &[],
+        art: "",
+    };
+
+    /// The art split into lines of coloured runs, without the colour markers.
+    pub fn lines(&self) -> Vec<Vec<(u8, String)>> {
+        let mut color = self.colors.first().copied().unwrap_or(7);
+        self.art
+            .lines()
+            .map(|line| {
+                let mut runs: Vec<(u8, String)> = Vec::new();
+                let mut text = String::new();
+                let mut chars = line.chars().peekable();
+                while let Some(c) = chars.next() {
+                    let index = match (c, chars.peek().and_then(|next| next.to_digit(10))) {
+                        ('$', Some(index)) if index > 0 => index as usize,
+                        _ => {
+                            text.push(c);
+                            continue;
+                        }
+                    };
+                    chars.next();
+                    if !text.is_empty() {
+                        runs.push((color, std::mem::take(&mut text)));
+                    }
+                    color = self.colors.get(index - 1).copied().unwrap_or(color);
+                }
+                if !text.is_empty() {
+                    runs.push((color, text));
+                }
+                runs
+            })
+            .collect()
+    }
+
+    /// Columns taken by the widest line.
+    pub fn width(&self) -> usize {
+        self.lines()
+            .iter()
+            .map(|runs| runs.iter().map(|(_, text)| text.chars().count()).sum())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.art.lines().count()
+    }
+}
+
+pub fn logo_by_id(id: &str) -> Option<&'static Logo> {
+    art::LOGOS.iter().find(|logo| logo.ids.contains(&id))
+}
+
+/// The logo for `ID`, else the one `LOGO` names (`archlinux-logo` for `archlinux`), else
+/// the first `ID_LIKE` we have one for, else Tux.
+pub fn logo_for(os_release: &OsRelease) -> &'static Logo {
+    let logo_name = os_release.logo.split("-logo").next().unwrap_or("");
+    std::iter::once(os_release.id.as_str())
+        .chain(std::iter::once(logo_name))
+        .chain(os_release.id_like.split_whitespace())
+        .find_map(|id| logo_by_id(&id.to_lowercase()))
+        .unwrap_or(&art::TUX)
+}
+
+/// Logo of the running system. Bedrock comes first since its os-release belongs to
+/// whichever stratum provides it.
+pub fn detect() -> &'static Logo {
+    if Path::new("/bedrock/etc/bedrock-release").is_file() {
+        return logo_by_id("bedrock").unwrap_or(&art::TUX);
+    }
+    match OsRelease::new() {
+        Ok(os_release) => logo_for(&os_release),
+        Err(_) => &art::TUX,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_logo_for() {
+        let release = |id: &str, id_like: &str| OsRelease {
+            id: id.to_string(),
+            id_like: id_like.to_string(),
+            ..OsRelease::default()
+        };
+        assert_eq!(logo_for(&release("arch", "")).ids[0], "arch");
+        assert_eq!(
+            logo_for(&release("zorin", "ubuntu debian")).ids[0],
+            "ubuntu"
+        );
+        assert_eq!(logo_for(&release("neon", "Debian")).ids[0], "debian");
+        assert_eq!(logo_for(&release("haiku", "")).ids[0], "linux");
+        for logo in art::LOGOS.iter().chain(std::iter::once(&art::TUX)) {
+            assert!(logo.width() <= 30 && logo.height() <= 12, "{}", logo.ids[0]);
+        }
+    }
+
+    #[test]
+    fn test_image_protocol() {
+        use image::{base64, is_png, kitty, protocol_from_env, protocol_from_response, Protocol};
+        assert!(is_png(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(!is_png(b"GIF89a"));
+        assert!(!is_png(b""));
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            protocol_from_env(env(&[("TERM", "xterm-kitty")])),
+            Some(Protocol::Kitty)
+        );
+        assert_eq!(
+            protocol_from_env(env(&[("TERM_PROGRAM", "iTerm.app")])),
+            Some(Protocol::Iterm2)
+        );
+        assert_eq!(protocol_from_env(env(&[("TERM", "xterm-256color")])), None);
+
+        assert_eq!(
+            protocol_from_response("\x1b_Gi=31;OK\x1b\\\x1b[?62;22c"),
+            Some(Protocol::Kitty)
+        );
+        assert_eq!(
+            protocol_from_response("\x1b[?62;4;6;22c"),
+            Some(Protocol::Sixel)
+        );
+        assert_eq!(protocol_from_response("\x1b[?1;2c"), None);
+        assert_eq!(protocol_from_response(""), None);
+
+        let png = vec![0u8; 3 * 4096];
+        let data = String::from_utf8(kitty(&png, 20, 10)).unwrap();
+        assert!(data.starts_with("\x1b_Gf=100,a=T,q=2,c=20,r=10,m=1;"));
+        assert_eq!(data.matches("\x1b_Gm=1;").count(), 2);
+        assert_eq!(data.matches("\x1b_Gm=0;").count(), 1);
+    }
+
+    #[test]
+    fn test_logo_lines() {
+        let logo = Logo {
+            ids: &["test"],
+            colors: &[4, 7],
+            art: "$1/\\ $2$$9\nok",
+        };
+        assert_eq!(
+            logo.lines(),
+            vec![
+                vec![(4, "/\\ ".to_string()), (7, "$".to_string())],
+                vec![(7, "ok".to_string())],
+            ]
+        );
+        assert_eq!(logo.width(), 4);
+    }
+}
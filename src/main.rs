@@ -1,3 +1,5 @@
+pub mod colors;
+pub mod logos;
 pub mod quotes;
 pub mod services;
 pub mod sys;
@@ -8,23 +10,92 @@ pub mod util;
 
 #[cfg(feature = "ui")]
 use crate::{
-    ui::{layout, App},
+    ui::{layout, App, Theme},
     util::event::{Config, Event, Events},
 };
 
+#[cfg(any(feature = "ui", feature = "terminal"))]
+use crate::colors::ColorDepth;
+#[cfg(any(feature = "ui", feature = "terminal"))]
+use crate::logos::{image::ImageLogo, ShownLogo};
 #[cfg(feature = "ui")]
-use std::{error::Error, io, time::Duration};
+use crate::sys::DiskFilter;
+#[cfg(any(feature = "ui", feature = "terminal"))]
+use crate::sys::{greeting::Greeting, DiskThresholds};
+#[cfg(any(feature = "ui", feature = "terminal"))]
+use std::io::{self, Write};
+#[cfg(feature = "ui")]
+use std::{error::Error, time::Duration};
+#[cfg(any(feature = "ui", feature = "terminal"))]
+use termion::cursor;
 #[cfg(feature = "ui")]
 use termion::{event::Key, input::MouseTerminal, raw::IntoRawMode, screen::AlternateScreen};
 #[cfg(feature = "ui")]
 use tui::{backend::TermionBackend, Terminal};
 
+/// Settings of both front ends, the ones only the TUI reads behind the `ui` feature.
 #[derive()]
-#[cfg(feature = "ui")]
+#[cfg(any(feature = "ui", feature = "terminal"))]
 struct Conf<'a> {
+    #[cfg(feature = "ui")]
     tick_rate: u64,
+    #[cfg(feature = "ui")]
     enhanced_graphics: bool,
     wanted_systemd_units: Vec<&'a str>,
+    #[cfg(feature = "ui")]
+    disk_filter: DiskFilter,
+    disk_thresholds: DiskThresholds,
+    /// Show the serial number in the Host section, only takes effect when running as root.
+    show_serial: bool,
+    /// A `Theme::preset` or a theme of its own. When unset, the dark theme with the
+    /// distro's os-release `ANSI_COLOR` as accent.
+    #[cfg(feature = "ui")]
+    theme: Option<Theme>,
+    /// Colours the terminal can show, detected from the environment when unset.
+    #[cfg(feature = "ui")]
+    color_depth: Option<ColorDepth>,
+    /// Header template and placeholder styles, see `sys::greeting` for the placeholders.
+    greeting: Greeting,
+    /// Show the distro logo next to the greeting.
+    #[cfg(feature = "ui")]
+    show_logo: bool,
+    /// Image shown instead of the ASCII logo on terminals with a graphics protocol.
+    #[cfg(feature = "ui")]
+    logo_image: Option<ImageLogo>,
+}
+
+#[cfg(any(feature = "ui", feature = "terminal"))]
+impl Default for Conf<'_> {
+    fn default() -> Self {
+        Conf {
+            #[cfg(feature = "ui")]
+            tick_rate: 60000,
+            #[cfg(feature = "ui")]
+            enhanced_graphics: true,
+            wanted_systemd_units: vec![
+                "fail2ban",
+                "plexmediaserver",
+                "samba",
+                "smartd",
+                "smbd",
+                "sshd",
+                "ufw",
+            ],
+            #[cfg(feature = "ui")]
+            disk_filter: DiskFilter::default(),
+            disk_thresholds: DiskThresholds::default(),
+            show_serial: false,
+            #[cfg(feature = "ui")]
+            theme: None,
+            #[cfg(feature = "ui")]
+            color_depth: None,
+            greeting: Greeting::default(),
+            #[cfg(feature = "ui")]
+            show_logo: true,
+            #[cfg(feature = "ui")]
+            logo_image: Some(ImageLogo::default()),
+        }
+    }
 }
 
 #[allow(unused_imports)]
@@ -43,18 +114,12 @@ Please run either:
     }
     #[cfg(feature = "ui")]
     {
-        let conf = Conf {
-            tick_rate: 60000,
-            enhanced_graphics: true,
-            wanted_systemd_units: vec![
-                "fail2ban",
-                "plexmediaserver",
-                "samba",
-                "smartd",
-                "smbd",
-                "sshd",
-                "ufw",
-            ],
+        let conf = Conf::default();
+        // Ask the terminal about graphics before the input thread starts reading stdin.
+        let logo = if conf.show_logo {
+            Some(ShownLogo::pick(conf.logo_image.as_ref()))
+        } else {
+            None
         };
         let events = Events::with_config(Config {
             tick_rate: Duration::from_millis(conf.tick_rate),
@@ -78,11 +143,29 @@ Please run either:
             "Neo Message of the Day",
             conf.enhanced_graphics,
             conf.wanted_systemd_units,
+            conf.disk_filter,
+            conf.disk_thresholds,
+            conf.show_serial,
+            conf.theme,
+            conf.color_depth.unwrap_or_else(ColorDepth::detect),
+            conf.greeting,
+            logo,
         );
+        // The image sits on top of the blank logo pane, redrawn whenever the pane moves.
+        let mut image_area = None;
         loop {
             terminal
                 .draw(|mut f| layout::draw(&mut f, &mut app))
                 .unwrap();
+            if let (Some(ShownLogo::Image(image)), Some(area)) = (&app.logo, app.logo_area) {
+                if image_area != Some(area) {
+                    let backend = terminal.backend_mut();
+                    write!(backend, "{}", cursor::Goto(area.x + 2, area.y + 2)).unwrap();
+                    backend.write_all(&image.data).unwrap();
+                    backend.flush().unwrap();
+                    image_area = Some(area);
+                }
+            }
 
             match events.next().unwrap() {
                 Event::Input(key) => match key {
@@ -95,6 +178,12 @@ Please run either:
                     Key::Down => {
                         app.on_down();
                     }
+                    Key::Right => {
+                        app.on_right();
+                    }
+                    Key::Left | Key::BackTab => {
+                        app.on_left();
+                    }
                     _ => {}
                 },
                 Event::Tick => {
@@ -111,95 +200,352 @@ Please run either:
         let (w, _h) = terminal_size().unwrap();
         let mem = mem_info().unwrap();
         let cpu = cpu_info().unwrap();
+        let conf = Conf::default();
         let disks = get_all_disks();
         let load = loadavg().unwrap();
         let pbu = process_by_user();
         let quote = get_quote();
+        let logins = login_summary();
+        let depth = ColorDepth::detect();
+        // Labels in red or yellow, like RHEL's, would read as warnings
+        let label = Paint::new(
+            OsRelease::new()
+                .ok()
+                .and_then(|os_release| os_release.color())
+                .filter(|color| {
+                    ![AnsiColor::Indexed(1), AnsiColor::Indexed(3)]
+                        .iter()
+                        .any(|status| is_close(*color, *status))
+                })
+                .or(Some(AnsiColor::Indexed(6))),
+            depth,
+        );
+        let red = Paint::ansi(1, depth);
+        let green = Paint::ansi(2, depth);
+        let yellow = Paint::ansi(3, depth);
+        let blue = Paint::ansi(4, depth);
+        let magenta = Paint::ansi(5, depth);
+        let white = Paint::ansi(7, depth);
+        let reset = Paint::new(None, depth);
 
-        println!(
+        let facts = GreetingFacts::gather(logins.last_login.as_ref());
+        for (field, value) in render(&conf.greeting.template, &facts) {
+            // Fields are bold green unless the greeting styles them
+            let field = field.map(|field| {
+                conf.greeting.style(field).unwrap_or(FieldStyle {
+                    color: Some(AnsiColor::Indexed(2)),
+                    bold: true,
+                })
+            });
+            match field {
+                Some(field) => print!(
+                    "{}{}{}{}{}",
+                    color::Fg(Paint::new(field.color, depth)),
+                    if field.bold {
+                        style::Bold.to_string()
+                    } else {
+                        String::new()
+                    },
+                    value,
+                    style::Reset,
+                    color::Fg(reset)
+                ),
+                None => print!("{}", value),
+            }
+        }
+        println!("\n");
+        let mut info = Vec::new();
+        info.push(format!(
             " - {}{}Hostname{}{}..: {}",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
+            color::Fg(reset),
             hostname().into_string().unwrap()
-        );
-        println!(
+        ));
+        info.push(format!(
             " - {}{}Distro{}{}....: {}",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
-            OsRelease::new().expect("Error Will Robinson").pretty_name
-        );
-        println!(
+            color::Fg(reset),
+            distro()
+        ));
+        info.push(format!(
             " - {}{}Kernel{}{}....: {}",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
+            color::Fg(reset),
             get_kernel().unwrap()
-        );
+        ));
+        let host = host_info(conf.show_serial);
+        if let Some(description) = host.description() {
+            info.push(format!(
+                " - {}{}Host{}{}......: {}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                description
+            ));
+        }
+        if let Some(firmware) = host.firmware() {
+            info.push(format!(
+                " - {}{}Firmware{}{}..: {}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                firmware
+            ));
+        }
+        if let Some(serial) = &host.serial {
+            info.push(format!(
+                " - {}{}Serial{}{}....: {}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                serial
+            ));
+        }
+        let virt = virt();
+        info.push(format!(
+            " - {}{}Virt{}{}......: {}",
+            color::Fg(label),
+            style::Bold,
+            style::Reset,
+            color::Fg(reset),
+            virt
+        ));
+        let logo = ShownLogo::pick(Some(&ImageLogo::default()));
+        print_beside_logo(&logo, &info, w, depth);
+        let arrays = md_arrays();
+        for array in arrays.iter().filter(|array| array.is_degraded()) {
+            println!(
+                " {}{}{} RAID degraded: {} {}{}",
+                color::Bg(red),
+                color::Fg(white),
+                style::Bold,
+                array,
+                style::Reset,
+                color::Bg(reset),
+            );
+        }
+        for reason in reboot_required() {
+            println!(
+                " {}{}{} {} {}{}",
+                color::Bg(red),
+                color::Fg(white),
+                style::Bold,
+                reason,
+                style::Reset,
+                color::Bg(reset),
+            );
+        }
+        if let Some(updates) = OsRelease::new()
+            .ok()
+            .and_then(|os_release| pending_updates(&os_release))
+        {
+            println!(
+                " - {}{}Updates{}{}...: {}{}{}{}{} pending{} ({})",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                if updates.security.unwrap_or(0) > 0 {
+                    color::Fg(red).to_string()
+                } else if updates.pending > 0 {
+                    color::Fg(yellow).to_string()
+                } else {
+                    color::Fg(green).to_string()
+                },
+                style::Bold,
+                updates.pending,
+                style::Reset,
+                color::Fg(reset),
+                match updates.security {
+                    Some(security) => format!(", {} security", security),
+                    None => String::new(),
+                },
+                updates.manager
+            );
+        }
         println!();
         println!(
             " - {}{}Uptime{}{}....: {}",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
+            color::Fg(reset),
             uptime()
         );
         println!(
             " - {}{}Load{}{}......: {}{}{}{}{} (1m), {}{}{}{}{} (5m), {}{}{}{}{} (15m)",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             load.one.to_string(),
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             load.five.to_string(),
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             load.fifteen.to_string(),
             style::Reset,
-            color::Fg(color::Reset),
+            color::Fg(reset),
         );
         println!(
             " - {}{}Proc{}{}......: {}{}{}{}{} (all), {}{}{}{}{} (root), {}{}{}{}{} (user)",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             pbu.all.to_string(),
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             pbu.root.to_string(),
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             pbu.user.to_string(),
             style::Reset,
-            color::Fg(color::Reset),
+            color::Fg(reset),
         );
+        println!(
+            " - {}{}States{}{}....: {} threads, {} zombie, {} blocked, {} stopped",
+            color::Fg(label),
+            style::Bold,
+            style::Reset,
+            color::Fg(reset),
+            pbu.threads,
+            pbu.zombies.len(),
+            pbu.blocked.len(),
+            pbu.stopped,
+        );
+        let owners: Vec<String> = pbu
+            .by_user
+            .iter()
+            .take(5)
+            .map(|(user, count)| format!("{} {}", user, count))
+            .collect();
+        println!(
+            " - {}{}Owners{}{}....: {}",
+            color::Fg(label),
+            style::Bold,
+            style::Reset,
+            color::Fg(reset),
+            owners.join(", ")
+        );
+        // A single run can't tell how long a process has been blocked, so only zombies warn
+        // and blocked processes are listed as they are.
+        for warning in pbu.warnings(&BlockedWatch::default(), Instant::now()) {
+            println!(
+                "     {}{}{}{}{}",
+                color::Fg(red),
+                style::Bold,
+                warning,
+                style::Reset,
+                color::Fg(reset)
+            );
+        }
+        if !pbu.blocked.is_empty() {
+            let blocked: Vec<String> = pbu
+                .blocked
+                .iter()
+                .take(5)
+                .map(|process| format!("{} ({})", process.command, process.pid))
+                .collect();
+            println!(
+                "     {}{} in uninterruptible sleep{}, how long is only tracked by the TUI",
+                color::Fg(yellow),
+                blocked.join(", "),
+                color::Fg(reset)
+            );
+        }
+        let sessions = sessions();
+        println!(
+            " - {}{}Users{}{}.....: {}{}{}{}{} logged in",
+            color::Fg(label),
+            style::Bold,
+            style::Reset,
+            color::Fg(reset),
+            color::Fg(green),
+            style::Bold,
+            sessions.len(),
+            style::Reset,
+            color::Fg(reset),
+        );
+        for session in sessions {
+            let line = format!(
+                "{:<10} {:<8} {:<20} {}  idle {}",
+                session.user,
+                session.tty,
+                if session.host.is_empty() {
+                    "-"
+                } else {
+                    &session.host
+                },
+                format_timestamp(session.login_time),
+                session
+                    .idle
+                    .map(format_idle)
+                    .unwrap_or_else(|| "?".to_string())
+            );
+            if session.current {
+                println!(
+                    "     {}{}{}{}{}",
+                    color::Fg(magenta),
+                    style::Bold,
+                    line,
+                    style::Reset,
+                    color::Fg(reset)
+                );
+            } else {
+                println!("     {}", line);
+            }
+        }
+        if let Some(failed) = logins.failed {
+            println!(
+                " - {}{}Failed{}{}....: {}{}{}{}{} since last login",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                if failed.count > 0 {
+                    color::Fg(red).to_string()
+                } else {
+                    color::Fg(green).to_string()
+                },
+                style::Bold,
+                failed.count,
+                style::Reset,
+                color::Fg(reset),
+            );
+            for (source, count) in failed.top_sources {
+                println!("     {:<40} {}", source, count);
+            }
+        }
         println!();
         println!(
             " - {}{}CPU{}{}.......: {}({}/{}) @ {}MHz",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
+            color::Fg(reset),
             cpu.name,
             cpu.physical_cores,
             cpu.logical_cores,
@@ -207,59 +553,136 @@ Please run either:
         );
         println!(
             " - {}{}Memory{}{}....: {}{}{}{}{} used, {}{}{}{}{} free, {}{}{}{}{} total",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
-            MemUnit::MiB(
-                (mem.total - mem.free - mem.cached - mem.buffers - mem.sreclaimable) as f64
-                    * 1024_f64
-            ),
+            MemUnit::MiB(mem.used() as f64 * 1024_f64),
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             MemUnit::MiB(mem.free as f64 * 1024_f64),
             style::Reset,
-            color::Fg(color::Reset),
-            color::Fg(color::Green),
+            color::Fg(reset),
+            color::Fg(green),
             style::Bold,
             MemUnit::MiB(mem.total as f64 * 1024_f64),
             style::Reset,
-            color::Fg(color::Reset)
+            color::Fg(reset)
         );
+        println!(
+            "   {} available, {} shared, {} slab, {} dirty",
+            MemUnit::MiB(mem.available as f64 * 1024_f64),
+            MemUnit::MiB(mem.shared as f64 * 1024_f64),
+            MemUnit::MiB(mem.slab as f64 * 1024_f64),
+            MemUnit::MiB(mem.dirty as f64 * 1024_f64)
+        );
+        if mem.swap_total > 0 {
+            println!(
+                " - {}{}Swap{}{}......: {}{}{:.0}%{}{} used ({} of {})",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                color::Fg(green),
+                style::Bold,
+                mem.swap_used_percent(),
+                style::Reset,
+                color::Fg(reset),
+                MemUnit::MiB(mem.swap_used() as f64 * 1024_f64),
+                MemUnit::MiB(mem.swap_total as f64 * 1024_f64)
+            );
+        }
+        let mut compression = Vec::new();
+        for zram in zram_devices() {
+            compression.push(format!("{} {:.1}x", zram.name, zram.ratio()));
+        }
+        if mem.zswap > 0 {
+            compression.push(format!(
+                "zswap {} holding {}",
+                MemUnit::MiB(mem.zswap as f64 * 1024_f64),
+                MemUnit::MiB(mem.zswapped as f64 * 1024_f64)
+            ));
+        }
+        if !compression.is_empty() {
+            println!(
+                " - {}{}Compress{}{}..: {}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                compression.join(", ")
+            );
+        }
+        if mem.hugepages_total > 0 {
+            println!(
+                " - {}{}Hugepages{}{}.: {}/{} free ({} each)",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                mem.hugepages_free,
+                mem.hugepages_total,
+                MemUnit::MiB(mem.hugepage_size as f64 * 1024_f64)
+            );
+        }
+        let batteries = if virt.is_container() {
+            Vec::new()
+        } else {
+            power_status().batteries
+        };
+        for battery in batteries {
+            println!(
+                " - {}{}Battery{}{}...: {} ({})",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                battery,
+                battery.name
+            );
+        }
+        let psi = pressure();
+        if let (Some(cpu), Some(memory), Some(io)) = (psi.cpu, psi.memory, psi.io) {
+            println!(
+                " - {}{}Pressure{}{}..: cpu {:.1}%, memory {:.1}%, io {:.1}%",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset),
+                cpu,
+                memory,
+                io
+            );
+        }
 
         println!(
             " - {}{}Volumes{}{}",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset)
+            color::Fg(reset)
         );
-        for disk in disks {
+        for disk in &disks {
+            let used = disk.used_percent();
             if disk.total_space >= 1_000_000_000 {
                 println!(
                     "     {}{}{}",
                     disk.mount_point.to_str().unwrap(),
                     " ".repeat(
-                        w as usize
-                            - (disk.mount_point.to_str().unwrap().len()
+                        (w as usize).saturating_sub(
+                            disk.mount_point.to_str().unwrap().len()
                                 + 13
-                                + ((((disk.total_space - disk.available_space) as f64
-                                    / disk.total_space as f64)
-                                    * 100.0)
-                                    .ceil()
-                                    .to_string()
+                                + (used.ceil().to_string()
                                     + "% out of "
                                     + &format!("{}", MemUnit::GB(disk.total_space as f64)))
-                                    .len())
+                                    .len()
+                        )
                     ),
-                    (((disk.total_space - disk.available_space) as f64 / disk.total_space as f64)
-                        * 100.0)
-                        .ceil()
-                        .to_string()
+                    used.ceil().to_string()
                         + "% out of "
                         + &format!("{}", MemUnit::GB(disk.total_space as f64))
                 );
@@ -268,22 +691,16 @@ Please run either:
                     "     {}{}{}",
                     disk.mount_point.to_str().unwrap(),
                     " ".repeat(
-                        w as usize
-                            - (disk.mount_point.to_str().unwrap().len()
+                        (w as usize).saturating_sub(
+                            disk.mount_point.to_str().unwrap().len()
                                 + 13
-                                + ((((disk.total_space - disk.available_space) as f64
-                                    / disk.total_space as f64)
-                                    * 100.0)
-                                    .ceil()
-                                    .to_string()
+                                + (used.ceil().to_string()
                                     + "% out of "
                                     + &format!("{}", MemUnit::MB(disk.total_space as f64)))
-                                    .len())
+                                    .len()
+                        )
                     ),
-                    (((disk.total_space - disk.available_space) as f64 / disk.total_space as f64)
-                        * 100.0)
-                        .ceil()
-                        .to_string()
+                    used.ceil().to_string()
                         + "% out of "
                         + &format!("{}", MemUnit::MB(disk.total_space as f64))
                 );
@@ -291,48 +708,105 @@ Please run either:
 
             println!(
                 "     [{}{}{}{}{}]",
-                color::Fg(color::Blue),
-                "=".repeat(
-                    ((w as f64 - 15.0)
-                        * ((disk.total_space - disk.available_space) as f64
-                            / disk.total_space as f64))
-                        .ceil() as usize
-                ),
-                color::Fg(color::Magenta),
-                "=".repeat(
-                    ((w as f64 - 15.0)
-                        * (1.0
-                            - ((disk.total_space - disk.available_space) as f64
-                                / disk.total_space as f64)))
-                        .floor() as usize
-                ),
-                color::Fg(color::Reset)
-            )
+                color::Fg(blue),
+                "=".repeat(((w as f64 - 15.0) * (used / 100.0)).ceil() as usize),
+                color::Fg(magenta),
+                "=".repeat(((w as f64 - 15.0) * (1.0 - used / 100.0)).floor() as usize),
+                color::Fg(reset)
+            );
+            let mut details = Vec::new();
+            if let Some(inodes) = disk.inodes_used_percent() {
+                details.push(format!("{:.0}% of inodes used", inodes.ceil()));
+            }
+            if disk.reserved_space() > 0 {
+                details.push(format!(
+                    "{} reserved",
+                    MemUnit::from_bytes(disk.reserved_space())
+                ));
+            }
+            if disk.read_only {
+                details.push("read-only".to_string());
+            }
+            if !details.is_empty() {
+                println!("     {}", details.join(", "));
+            }
+        }
+        for warning in disks
+            .iter()
+            .flat_map(|disk| disk.warnings(&conf.disk_thresholds))
+        {
+            println!(
+                "     {}{}{}{}{}",
+                color::Fg(red),
+                style::Bold,
+                warning,
+                style::Reset,
+                color::Fg(reset)
+            );
+        }
+        let pools = zfs_pools();
+        let filesystems = btrfs_filesystems();
+        let smart = if virt.is_bare_metal() {
+            smart_health()
+        } else {
+            Vec::new()
+        };
+        if !arrays.is_empty() || !pools.is_empty() || !filesystems.is_empty() || !smart.is_empty() {
+            println!(
+                "\n - {}{}Storage{}{}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset)
+            );
+            let healthy = arrays
+                .iter()
+                .map(|array| (array.to_string(), array.active && !array.is_degraded()))
+                .chain(
+                    pools
+                        .iter()
+                        .map(|pool| (pool.to_string(), pool.is_healthy())),
+                )
+                .chain(
+                    filesystems
+                        .iter()
+                        .map(|fs| (fs.to_string(), fs.error_count() == 0)),
+                )
+                .chain(
+                    smart
+                        .iter()
+                        .map(|disk| (disk.to_string(), disk.is_healthy())),
+                );
+            for (line, healthy) in healthy {
+                if healthy {
+                    println!("     {}", line);
+                } else {
+                    println!(
+                        "     {}{}{}{}{}",
+                        color::Fg(red),
+                        style::Bold,
+                        line,
+                        style::Reset,
+                        color::Fg(reset)
+                    );
+                }
+            }
         }
         println!(
             "\n - {}{}Systemd Services{}{}",
-            color::Fg(color::Cyan),
+            color::Fg(label),
             style::Bold,
             style::Reset,
-            color::Fg(color::Reset)
+            color::Fg(reset)
         );
         for sd_unit in list_unit_files().unwrap() {
             if !sd_unit.name.is_empty()
-                && vec![
-                    "fail2ban",
-                    "plexmediaserver",
-                    "samba",
-                    "smartd",
-                    "smbd",
-                    "sshd",
-                    "ufw",
-                ]
-                .contains(&sd_unit.name.as_str())
+                && conf.wanted_systemd_units.contains(&sd_unit.name.as_str())
             {
                 match sd_unit.state {
                     systemd::UnitState::Enabled | systemd::UnitState::EnabledRuntime => println!(
                         "     {}{} {}{}{}",
-                        color::Fg(color::Green),
+                        color::Fg(green),
                         style::Bold,
                         sd_unit.name,
                         style::Bold,
@@ -343,7 +817,7 @@ Please run either:
                     | systemd::UnitState::Disabled
                     | systemd::UnitState::Bad => println!(
                         "     {}{} {}{}{}",
-                        color::Fg(color::Red),
+                        color::Fg(red),
                         style::Bold,
                         sd_unit.name,
                         style::Bold,
@@ -351,7 +825,7 @@ Please run either:
                     ),
                     _ => println!(
                         "     {}{}卑 {}{}{}",
-                        color::Fg(color::Yellow),
+                        color::Fg(yellow),
                         style::Bold,
                         sd_unit.name,
                         style::Bold,
@@ -361,33 +835,158 @@ Please run either:
             }
         }
 
+        // Without a previous sample, CPU% is each process' lifetime average, like ps(1).
+        let sampler = ProcessSampler::new();
+        for (title, sort) in &[
+            ("Top CPU", ProcessSort::Cpu),
+            ("Top Memory", ProcessSort::Memory),
+        ] {
+            println!(
+                "\n - {}{}{}{}{}",
+                color::Fg(label),
+                style::Bold,
+                title,
+                style::Reset,
+                color::Fg(reset)
+            );
+            for process in sampler.top(*sort, 5) {
+                println!(
+                    "     {:>7} {:<10} {:>5.1}% {:>10} {:>5} {}",
+                    process.pid,
+                    process.user,
+                    process.cpu_percent,
+                    MemUnit::from_bytes(process.rss).to_string(),
+                    format_idle(process.runtime),
+                    process.command.chars().take(48).collect::<String>()
+                );
+            }
+        }
+
         if let Some(processes) = get_docker_processes() {
             println!(
                 "\n - {}{}Docker Containers{}{}",
-                color::Fg(color::Cyan),
+                color::Fg(label),
                 style::Bold,
                 style::Reset,
-                color::Fg(color::Reset)
+                color::Fg(reset)
             );
             for process in processes {
                 println!("     {} {}", process.0, process.1);
             }
         }
 
+        let ports = listening_ports();
+        if !ports.is_empty() {
+            println!(
+                "\n - {}{}Listening{}{}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset)
+            );
+            for port in ports {
+                println!(
+                    "     {:<4} {:<28} {}",
+                    port.protocol,
+                    port.address,
+                    port.process.unwrap_or_else(|| "-".to_string())
+                );
+            }
+        }
+        let connections = connections_by_remote();
+        if !connections.is_empty() {
+            println!(
+                "\n - {}{}Connections{}{}",
+                color::Fg(label),
+                style::Bold,
+                style::Reset,
+                color::Fg(reset)
+            );
+            for remote in connections.iter().take(5) {
+                println!(
+                    "     {:<40} {}{}{}{}{}",
+                    remote.address,
+                    color::Fg(green),
+                    style::Bold,
+                    remote.count,
+                    style::Reset,
+                    color::Fg(reset)
+                );
+            }
+        }
+
         println!("\n{}", fill(quote.quote, w as usize));
         // Print Author
         println!("\n\t- {}", quote.author);
     }
 }
 
+/// Prints `info` to the right of `logo`, or on its own when the terminal is too narrow.
+#[cfg(feature = "terminal")]
+fn print_beside_logo(logo: &ShownLogo, info: &[String], width: u16, depth: ColorDepth) {
+    let logo_width = logo.width();
+    if (width as usize) < logo_width + 60 {
+        for line in info {
+            println!("{}", line);
+        }
+        return;
+    }
+    match logo {
+        ShownLogo::Ascii(logo) => {
+            let art = logo.lines();
+            for i in 0..art.len().max(info.len()) {
+                let runs = art.get(i).map(Vec::as_slice).unwrap_or(&[]);
+                let mut columns = 0;
+                for (ansi, text) in runs {
+                    print!(
+                        "{}{}{}{}",
+                        color::Fg(Paint::ansi(*ansi, depth)),
+                        style::Bold,
+                        text,
+                        style::Reset
+                    );
+                    columns += text.chars().count();
+                }
+                print!(
+                    "{}{:pad$}",
+                    color::Fg(Paint::new(None, depth)),
+                    "",
+                    pad = logo_width - columns + 2
+                );
+                println!("{}", info.get(i).map(String::as_str).unwrap_or(""));
+            }
+        }
+        ShownLogo::Image(image) => {
+            // Make room first so the screen doesn't scroll under the saved cursor.
+            let rows = image.rows as usize;
+            print!("{}{}", "\n".repeat(rows), cursor::Up(image.rows));
+            print!("{}", cursor::Save);
+            io::stdout().write_all(&image.data).unwrap();
+            print!("{}", cursor::Restore);
+            for line in info {
+                println!("{}{}", cursor::Right(image.columns + 2), line);
+            }
+            print!("{}", "\n".repeat(rows.saturating_sub(info.len())));
+        }
+    }
+}
+
+#[cfg(feature = "terminal")]
+use colors::{is_close, Paint};
 #[cfg(feature = "terminal")]
 use quotes::get_quote;
 #[cfg(feature = "terminal")]
 use services::{get_docker_processes, list_unit_files, systemd};
 #[cfg(feature = "terminal")]
+use std::time::Instant;
+#[cfg(feature = "terminal")]
 use sys::{
-    cpu_info, get_all_disks, get_kernel, hostname, loadavg, mem_info, process_by_user, uptime,
-    MemUnit, OsRelease,
+    btrfs_filesystems, connections_by_remote, cpu_info, distro, format_idle, format_timestamp,
+    get_all_disks, get_kernel, greeting::render, greeting::FieldStyle, host_info, hostname,
+    listening_ports, loadavg, login_summary, md_arrays, mem_info, pending_updates, power_status,
+    pressure, process_by_user, reboot_required, sessions, smart_health, uptime, virt, zfs_pools,
+    zram_devices, AnsiColor, BlockedWatch, GreetingFacts, MemUnit, OsRelease, ProcessSampler,
+    ProcessSort,
 };
 #[cfg(feature = "terminal")]
 use termion::{color, style, terminal_size};
@@ -0,0 +1,68 @@
+use dbus::{
+    blocking::{stdintf::org_freedesktop_dbus::Properties, Connection},
+    Path,
+};
+use std::time::Duration;
+
+/// Session id, uid, user name, seat id and object path, as returned by `ListSessions`.
+type SessionListing = (String, u32, String, String, Path<'static>);
+
+/// A session as reported by `org.freedesktop.login1`.
+pub struct LogindSession {
+    pub id: String,
+    pub uid: u32,
+    pub user: String,
+    pub tty: String,
+    pub remote_host: String,
+    /// Seconds since the epoch when the session was created.
+    pub login_time: u64,
+    /// Seconds since the epoch from which the session is considered idle, if it is.
+    pub idle_since: Option<u64>,
+}
+
+pub fn list_sessions() -> Result<Vec<LogindSession>, Box<dyn std::error::Error>> {
+    let conn = Connection::new_system()?;
+    let manager = conn.with_proxy(
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        Duration::from_millis(5000),
+    );
+
+    let (sessions,): (Vec<SessionListing>,) =
+        manager.method_call("org.freedesktop.login1.Manager", "ListSessions", ())?;
+
+    let mut ret = Vec::with_capacity(sessions.len());
+    for (id, uid, user, _seat, path) in sessions {
+        let session = conn.with_proxy("org.freedesktop.login1", path, Duration::from_millis(5000));
+        let tty: String = session
+            .get("org.freedesktop.login1.Session", "TTY")
+            .unwrap_or_default();
+        let remote_host: String = session
+            .get("org.freedesktop.login1.Session", "RemoteHost")
+            .unwrap_or_default();
+        // Timestamps are exported in microseconds of CLOCK_REALTIME.
+        let login_time: u64 = session
+            .get("org.freedesktop.login1.Session", "Timestamp")
+            .unwrap_or_default();
+        let idle_hint: bool = session
+            .get("org.freedesktop.login1.Session", "IdleHint")
+            .unwrap_or_default();
+        let idle_since: u64 = session
+            .get("org.freedesktop.login1.Session", "IdleSinceHint")
+            .unwrap_or_default();
+        ret.push(LogindSession {
+            id,
+            uid,
+            user,
+            tty,
+            remote_host,
+            login_time: login_time / 1_000_000,
+            idle_since: if idle_hint && idle_since > 0 {
+                Some(idle_since / 1_000_000)
+            } else {
+                None
+            },
+        })
+    }
+    Ok(ret)
+}
@@ -1,7 +1,9 @@
 pub mod docker;
+pub mod logind;
 pub mod systemd;
 
 pub use self::docker::get_docker_processes;
+pub use self::logind::list_sessions;
 pub use self::systemd::list_unit_files;
 pub use self::systemd::SystemdUnit;
 
@@ -0,0 +1,166 @@
+// btrfs file systems, from `/sys/fs/btrfs`. Free space on btrfs depends on how chunks are
+// allocated per profile, so statvfs alone doesn't tell whether a file system is filling up.
+
+use super::MemUnit;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BtrfsFilesystem {
+    pub uuid: String,
+    pub label: String,
+    /// Member block devices, e.g. `sda2`.
+    pub devices: Vec<String>,
+    pub allocation: Vec<BtrfsAllocation>,
+    pub device_errors: Vec<BtrfsDeviceErrors>,
+}
+
+impl BtrfsFilesystem {
+    pub fn error_count(&self) -> u64 {
+        self.device_errors
+            .iter()
+            .map(BtrfsDeviceErrors::total)
+            .sum()
+    }
+}
+
+impl fmt::Display for BtrfsFilesystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = if self.label.is_empty() {
+            &self.uuid
+        } else {
+            &self.label
+        };
+        write!(f, "{} ({})", name, self.devices.join(", "))?;
+        for allocation in &self.allocation {
+            write!(
+                f,
+                ", {} {} of {}",
+                allocation.kind,
+                MemUnit::from_bytes(allocation.used),
+                MemUnit::from_bytes(allocation.total)
+            )?;
+        }
+        match self.error_count() {
+            0 => Ok(()),
+            1 => write!(f, ", 1 device error"),
+            errors => write!(f, ", {} device errors", errors),
+        }
+    }
+}
+
+/// Space allocated to chunks of one kind (`data`, `metadata` or `system`) and how much of it
+/// is used, in bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BtrfsAllocation {
+    pub kind: String,
+    pub total: u64,
+    pub used: u64,
+}
+
+/// The counters `btrfs device stats` prints, exposed in sysfs since Linux 5.14.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BtrfsDeviceErrors {
+    pub devid: String,
+    pub write: u64,
+    pub read: u64,
+    pub flush: u64,
+    pub corruption: u64,
+    pub generation: u64,
+}
+
+impl BtrfsDeviceErrors {
+    pub fn total(&self) -> u64 {
+        self.write + self.read + self.flush + self.corruption + self.generation
+    }
+}
+
+/// Parses a `devinfo/<devid>/error_stats` file.
+pub fn parse_error_stats(devid: &str, content: &str) -> BtrfsDeviceErrors {
+    let mut errors = BtrfsDeviceErrors {
+        devid: devid.to_string(),
+        ..BtrfsDeviceErrors::default()
+    };
+    for line in content.lines() {
+        let mut split = line.split_whitespace();
+        let (key, value) = match (split.next(), split.next().and_then(|v| v.parse().ok())) {
+            (Some(key), Some(value)) => (key, value),
+            _ => continue,
+        };
+        match key {
+            "write_errs" => errors.write = value,
+            "read_errs" => errors.read = value,
+            "flush_errs" => errors.flush = value,
+            "corruption_errs" => errors.corruption = value,
+            "generation_errs" => errors.generation = value,
+            _ => {}
+        }
+    }
+    errors
+}
+
+fn read_trimmed(path: PathBuf) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_u64(path: PathBuf) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+fn dir_names(path: PathBuf) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+pub fn btrfs_filesystems() -> Vec<BtrfsFilesystem> {
+    btrfs_filesystems_in(Path::new("/sys/fs/btrfs"))
+}
+
+/// Reads every file system below `root`, which is `/sys/fs/btrfs` outside of tests.
+pub fn btrfs_filesystems_in(root: &Path) -> Vec<BtrfsFilesystem> {
+    dir_names(root.to_path_buf())
+        .into_iter()
+        // Besides one directory per file system, there's `features`
+        .filter(|uuid| root.join(uuid).join("allocation").is_dir())
+        .map(|uuid| {
+            let dir = root.join(&uuid);
+            let allocation = ["data", "metadata", "system"]
+                .iter()
+                .filter_map(|kind| {
+                    let kind_dir = dir.join("allocation").join(kind);
+                    Some(BtrfsAllocation {
+                        kind: kind.to_string(),
+                        total: read_u64(kind_dir.join("total_bytes"))?,
+                        used: read_u64(kind_dir.join("bytes_used"))?,
+                    })
+                })
+                .collect();
+            let device_errors = dir_names(dir.join("devinfo"))
+                .into_iter()
+                .filter_map(|devid| {
+                    let content =
+                        fs::read_to_string(dir.join("devinfo").join(&devid).join("error_stats"))
+                            .ok()?;
+                    Some(parse_error_stats(&devid, &content))
+                })
+                .collect();
+            BtrfsFilesystem {
+                label: read_trimmed(dir.join("label")).unwrap_or_default(),
+                devices: dir_names(dir.join("devices")),
+                allocation,
+                device_errors,
+                uuid,
+            }
+        })
+        .collect()
+}
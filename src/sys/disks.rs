@@ -1,7 +1,7 @@
 use libc::statvfs;
 use std::{
     ffi::{OsStr, OsString},
-    fs::File,
+    fs::{self, File},
     io::{self, Read, Seek},
     mem,
     os::unix::ffi::OsStrExt,
@@ -10,20 +10,53 @@ use std::{
 
 // This source code was adopted from https://github.com/GuillaumeGomez/sysinfo
 
-fn find_type_for_name(name: &OsStr) -> DiskType {
-    /* turn "sda1" into "sda": */
-    let mut trimmed: &[u8] = name.as_bytes();
-    while trimmed.len() > 1
-        && trimmed[trimmed.len() - 1] >= b'0'
-        && trimmed[trimmed.len() - 1] <= b'9'
+/// Disks whose names end in a digit, so their partitions put a `p` before the number.
+const DIGIT_SUFFIXED_DISKS: [&str; 5] = ["nvme", "mmcblk", "md", "nbd", "loop"];
+
+/// Turns a partition name into its disk's: "sda1" into "sda", "nvme0n1p2" into "nvme0n1".
+/// Whole disks are left as they are.
+pub fn trim_partition(name: &str) -> &str {
+    if DIGIT_SUFFIXED_DISKS
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
     {
-        trimmed = &trimmed[..trimmed.len() - 1]
+        return match name.rfind('p') {
+            Some(p)
+                if name[..p].ends_with(|c: char| c.is_ascii_digit())
+                    && name.len() > p + 1
+                    && name[p + 1..].bytes().all(|c| c.is_ascii_digit()) =>
+            {
+                &name[..p]
+            }
+            _ => name,
+        };
+    }
+    let trimmed = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if trimmed.is_empty() {
+        name
+    } else {
+        trimmed
+    }
+}
+
+/// The whole disk a block device is part of, as named in `/sys/block` and `/proc/diskstats`.
+pub fn parent_device(name: &str) -> String {
+    // `/dev/mapper/*` and `/dev/root` are links to the actual node (`dm-0`, `sda2`, ...)
+    let name = fs::canonicalize(Path::new("/dev").join(name))
+        .ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().to_string()))
+        .unwrap_or_else(|| name.to_string());
+    if Path::new("/sys/block").join(&name).exists() {
+        name
+    } else {
+        trim_partition(&name).to_string()
     }
-    let trimmed: &OsStr = OsStrExt::from_bytes(trimmed);
+}
 
+pub fn find_type_for_name(name: &OsStr) -> DiskType {
     let path = Path::new("/sys/block/")
         .to_owned()
-        .join(trimmed)
+        .join(parent_device(&name.to_string_lossy()))
         .join("queue/rotational");
     // Normally, this file only contains '0' or '1' but just in case, we get 8 bytes...
     let rotational_int = get_all_data(path, 8).unwrap_or_default().trim().parse();
@@ -36,26 +69,36 @@ macro_rules! cast {
     };
 }
 
-pub fn new_disk(name: &OsStr, mount_point: &Path, file_system: &[u8]) -> Disk {
+/// Sizes are counted in fragments (`f_frsize`); `f_bsize` is only the preferred I/O size and
+/// differs from it on some file systems.
+fn stat_disk(mount_point: &Path) -> Option<statvfs> {
     let mount_point_cpath = to_cpath(mount_point);
-    let type_ = find_type_for_name(name);
-    let mut total = 0;
-    let mut available = 0;
     unsafe {
         let mut stat: statvfs = mem::zeroed();
         if statvfs(mount_point_cpath.as_ptr() as *const _, &mut stat) == 0 {
-            total = cast!(stat.f_bsize) * cast!(stat.f_blocks);
-            available = cast!(stat.f_bsize) * cast!(stat.f_bavail);
+            Some(stat)
+        } else {
+            None
         }
     }
-    Disk {
+}
+
+pub fn new_disk(name: &OsStr, mount_point: &Path, file_system: &[u8]) -> Disk {
+    let type_ = find_type_for_name(name);
+    let mut disk = Disk {
         type_,
         name: name.to_owned(),
         file_system: file_system.to_owned(),
         mount_point: mount_point.to_owned(),
-        total_space: cast!(total),
-        available_space: cast!(available),
-    }
+        total_space: 0,
+        available_space: 0,
+        free_space: 0,
+        inodes_total: 0,
+        inodes_free: 0,
+        read_only: false,
+    };
+    disk.refresh();
+    disk
 }
 
 /// Struct containing a disk information.
@@ -65,7 +108,83 @@ pub struct Disk {
     pub file_system: Vec<u8>,
     pub mount_point: PathBuf,
     pub total_space: u64,
+    /// Space available to unprivileged users.
     pub available_space: u64,
+    /// Free space including the blocks reserved for root.
+    pub free_space: u64,
+    /// Zero on file systems without a fixed inode table, like btrfs.
+    pub inodes_total: u64,
+    /// Inodes available to unprivileged users.
+    pub inodes_free: u64,
+    pub read_only: bool,
+}
+
+impl Disk {
+    pub fn used_space(&self) -> u64 {
+        self.total_space.saturating_sub(self.free_space)
+    }
+
+    /// Space only root can use, usually 5% of ext file systems.
+    pub fn reserved_space(&self) -> u64 {
+        self.free_space.saturating_sub(self.available_space)
+    }
+
+    /// Used share of the space unprivileged users can have, the way `df` reports it.
+    pub fn used_percent(&self) -> f64 {
+        let usable = self.used_space() + self.available_space;
+        if usable == 0 {
+            0.0
+        } else {
+            self.used_space() as f64 / usable as f64 * 100.0
+        }
+    }
+
+    pub fn inodes_used_percent(&self) -> Option<f64> {
+        if self.inodes_total == 0 {
+            None
+        } else {
+            let used = self.inodes_total.saturating_sub(self.inodes_free);
+            Some(used as f64 / self.inodes_total as f64 * 100.0)
+        }
+    }
+
+    /// Warnings for space or inode usage at or above `thresholds`.
+    pub fn warnings(&self, thresholds: &DiskThresholds) -> Vec<String> {
+        let mount_point = self.mount_point.to_string_lossy();
+        let mut warnings = Vec::new();
+        if self.total_space > 0 && self.used_percent() >= thresholds.space {
+            warnings.push(format!(
+                "{} is {:.0}% full",
+                mount_point,
+                self.used_percent()
+            ));
+        }
+        if let Some(inodes) = self.inodes_used_percent() {
+            if inodes >= thresholds.inodes {
+                warnings.push(format!(
+                    "{} has used {:.0}% of its inodes",
+                    mount_point, inodes
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+/// Usage percentages from which disks are flagged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskThresholds {
+    pub space: f64,
+    pub inodes: f64,
+}
+
+impl Default for DiskThresholds {
+    fn default() -> DiskThresholds {
+        DiskThresholds {
+            space: 90.0,
+            inodes: 90.0,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -198,52 +317,217 @@ impl DiskExt for Disk {
     }
 
     fn refresh(&mut self) -> bool {
-        unsafe {
-            let mut stat: statvfs = mem::zeroed();
-            let mount_point_cpath = to_cpath(&self.mount_point);
-            if statvfs(mount_point_cpath.as_ptr() as *const _, &mut stat) == 0 {
-                let tmp = cast!(stat.f_bsize) * cast!(stat.f_bavail);
-                self.available_space = cast!(tmp);
+        match stat_disk(&self.mount_point) {
+            Some(stat) => {
+                let fragment = cast!(stat.f_frsize);
+                self.total_space = fragment * cast!(stat.f_blocks);
+                self.available_space = fragment * cast!(stat.f_bavail);
+                self.free_space = fragment * cast!(stat.f_bfree);
+                self.inodes_total = cast!(stat.f_files);
+                self.inodes_free = cast!(stat.f_favail);
+                self.read_only = stat.f_flag & libc::ST_RDONLY != 0;
                 true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Which field of a mount a [`DiskRule`] glob is matched against.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiskRule {
+    /// The mount source, e.g. `/dev/sda1`, `tank/home` or `server:/export`.
+    Device(String),
+    /// The file system type, e.g. `ext4` or `nfs4`.
+    FileSystem(String),
+    MountPoint(String),
+}
+
+impl DiskRule {
+    pub fn matches(&self, mount: &MountInfo) -> bool {
+        match self {
+            DiskRule::Device(pattern) => glob_match(pattern, &mount.source),
+            DiskRule::FileSystem(pattern) => glob_match(pattern, &mount.file_system),
+            DiskRule::MountPoint(pattern) => {
+                glob_match(pattern, &mount.mount_point.to_string_lossy())
+            }
+        }
+    }
+}
+
+/// A mount is shown when it matches any `include` rule and no `exclude` rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskFilter {
+    pub include: Vec<DiskRule>,
+    pub exclude: Vec<DiskRule>,
+}
+
+impl Default for DiskFilter {
+    /// Block devices (whatever their prefix: `sd`, `nvme`, `vd`, `xvd`, `mmcblk`, device mapper
+    /// ...), ZFS datasets and network shares, leaving out loop devices since those are
+    /// mostly snaps.
+    fn default() -> DiskFilter {
+        DiskFilter {
+            include: vec![
+                DiskRule::Device("/dev/*".to_string()),
+                DiskRule::FileSystem("zfs".to_string()),
+                DiskRule::FileSystem("nfs*".to_string()),
+                DiskRule::FileSystem("cifs".to_string()),
+                DiskRule::FileSystem("smb3".to_string()),
+            ],
+            exclude: vec![
+                DiskRule::Device("/dev/loop*".to_string()),
+                DiskRule::FileSystem("squashfs".to_string()),
+            ],
+        }
+    }
+}
+
+impl DiskFilter {
+    pub fn matches(&self, mount: &MountInfo) -> bool {
+        self.include.iter().any(|rule| rule.matches(mount))
+            && !self.exclude.iter().any(|rule| rule.matches(mount))
+    }
+}
+
+/// Shell style glob supporting `*` and `?`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), text.chars().collect());
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was and how much of the text it swallowed so far
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A line of `/proc/self/mountinfo`, see proc(5).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MountInfo {
+    /// `major:minor` of the device, shared by bind mounts of the same file system.
+    pub device_id: (u32, u32),
+    pub mount_point: PathBuf,
+    pub file_system: String,
+    pub source: String,
+    /// The btrfs subvolume mounted, `None` for other file systems.
+    pub subvolume: Option<String>,
+}
+
+/// Undoes the octal escapes (`\040` for a space, `\011`, `\012`, `\134`) the kernel uses for
+/// whitespace and backslashes in paths.
+pub fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 4).and_then(|digits| {
+            if bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)) {
+                u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok()
             } else {
-                false
+                None
+            }
+        });
+        match escape {
+            Some(byte) => {
+                unescaped.push(byte);
+                i += 4;
+            }
+            None => {
+                unescaped.push(bytes[i]);
+                i += 1;
             }
         }
     }
+    String::from_utf8_lossy(&unescaped).into_owned()
 }
 
-pub fn get_all_disks() -> Vec<Disk> {
-    let content = get_all_data("/proc/mounts", 16_385).unwrap_or_default();
-    let disks = content.lines().filter(|line| {
-        let line = line.trim_start();
-        // While the `sd` prefix is most common, some disks instead use the `nvme` prefix. This
-        // prefix refers to NVM (non-volatile memory) cabale SSDs. These disks run on the NVMe
-        // storage controller protocol (not the scsi protocol) and as a result use a different
-        // prefix to support NVMe namespaces.
-        //
-        // In some other cases, it uses a device mapper to map physical block devices onto
-        // higher-level virtual block devices (on `/dev/mapper`).
-        //
-        // Raspbian uses root and mmcblk for physical disks
-        line.starts_with("/dev/sd")
-            || line.starts_with("/dev/nvme")
-            || line.starts_with("/dev/mapper/")
-            || line.starts_with("/dev/root")
-            || line.starts_with("/dev/mmcblk")
-    });
-    let mut ret = vec![];
-
-    for line in disks {
-        let mut split = line.split(' ');
-        if let (Some(name), Some(mountpt), Some(fs)) = (split.next(), split.next(), split.next()) {
-            ret.push(new_disk(
-                name[5..].as_ref(),
-                Path::new(mountpt),
-                fs.as_bytes(),
-            ));
+pub fn parse_mountinfo(content: &str) -> Vec<MountInfo> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let device_id = fields.nth(2)?;
+            let mut device_id = device_id.split(':').map(str::parse::<u32>);
+            let device_id = (device_id.next()?.ok()?, device_id.next()?.ok()?);
+            let root = unescape_octal(fields.next()?);
+            let mount_point = unescape_octal(fields.next()?);
+            // Skip the variable number of optional fields up to the separator
+            let mut fields = fields.skip_while(|field| *field != "-").skip(1);
+            let file_system = unescape_octal(fields.next()?);
+            let source = unescape_octal(fields.next()?);
+            let subvolume = if file_system == "btrfs" {
+                // Older kernels don't list `subvol=`, the root is the subvolume there
+                let subvol = fields.next().and_then(|options| {
+                    options
+                        .split(',')
+                        .find_map(|option| option.strip_prefix("subvol="))
+                });
+                Some(subvol.map(unescape_octal).unwrap_or(root))
+            } else {
+                None
+            };
+            Some(MountInfo {
+                device_id,
+                mount_point: PathBuf::from(mount_point),
+                file_system,
+                source,
+                subvolume,
+            })
+        })
+        .collect()
+}
+
+/// Mounts matching `filter`, keeping a single mount per device and btrfs subvolume: bind
+/// mounts would otherwise show the same space several times.
+pub fn filter_mounts(mounts: Vec<MountInfo>, filter: &DiskFilter) -> Vec<MountInfo> {
+    let mut kept: Vec<MountInfo> = Vec::new();
+    for mount in mounts.into_iter().filter(|mount| filter.matches(mount)) {
+        let same =
+            |k: &&mut MountInfo| k.device_id == mount.device_id && k.subvolume == mount.subvolume;
+        match kept.iter_mut().find(same) {
+            // Prefer the shortest mount point, usually the one users know
+            Some(k) if mount.mount_point.as_os_str().len() < k.mount_point.as_os_str().len() => {
+                *k = mount
+            }
+            Some(_) => {}
+            None => kept.push(mount),
         }
     }
-    ret
+    kept
+}
+
+pub fn get_all_disks() -> Vec<Disk> {
+    get_disks(&DiskFilter::default())
+}
+
+pub fn get_disks(filter: &DiskFilter) -> Vec<Disk> {
+    let content = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    filter_mounts(parse_mountinfo(&content), filter)
+        .into_iter()
+        .map(|mount| {
+            // `sda1` for `/dev/sda1`, other sources keep their full name
+            let name = mount.source.strip_prefix("/dev/").unwrap_or(&mount.source);
+            new_disk(
+                name.as_ref(),
+                &mount.mount_point,
+                mount.file_system.as_bytes(),
+            )
+        })
+        .collect()
 }
 
 pub fn to_cpath(path: &Path) -> Vec<u8> {
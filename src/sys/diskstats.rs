@@ -0,0 +1,107 @@
+// Disk throughput and latency, computed from the difference between two reads of
+// `/proc/diskstats`. See Documentation/admin-guide/iostats.rst in the kernel tree.
+
+use super::disks::{parent_device, Disk};
+use procfs::{diskstats, DiskStat};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// `/proc/diskstats` counts sectors of 512 bytes, whatever the device's sector size.
+const SECTOR_SIZE: f64 = 512.0;
+
+/// How many samples the sparklines keep.
+const HISTORY_LEN: usize = 60;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskIo {
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Completed reads and writes per second.
+    pub iops: f64,
+    /// Average time a read or write took, queueing included. `None` when there was no I/O.
+    pub await_ms: Option<f64>,
+}
+
+/// I/O rates between two samples of the same device taken `elapsed` apart.
+pub fn disk_io(previous: &DiskStat, current: &DiskStat, elapsed: Duration) -> DiskIo {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return DiskIo::default();
+    }
+    let delta = |previous: usize, current: usize| current.saturating_sub(previous) as f64;
+    let ios = delta(previous.reads, current.reads) + delta(previous.writes, current.writes);
+    let io_time = delta(previous.time_reading, current.time_reading)
+        + delta(previous.time_writing, current.time_writing);
+    DiskIo {
+        read_bytes_per_sec: delta(previous.sectors_read, current.sectors_read) * SECTOR_SIZE / secs,
+        write_bytes_per_sec: delta(previous.sectors_written, current.sectors_written) * SECTOR_SIZE
+            / secs,
+        iops: ios / secs,
+        await_ms: if ios > 0.0 { Some(io_time / ios) } else { None },
+    }
+}
+
+/// Keeps the previous `/proc/diskstats` sample around to turn its counters into rates.
+pub struct DiskIoSampler {
+    previous: HashMap<String, DiskStat>,
+    sampled_at: Instant,
+    pub rates: HashMap<String, DiskIo>,
+    /// Read plus written bytes per second of each device, oldest first.
+    pub history: HashMap<String, Vec<u64>>,
+}
+
+impl DiskIoSampler {
+    pub fn new() -> DiskIoSampler {
+        DiskIoSampler {
+            previous: read_diskstats(),
+            sampled_at: Instant::now(),
+            rates: HashMap::new(),
+            history: HashMap::new(),
+        }
+    }
+
+    pub fn sample(&mut self) {
+        let current = read_diskstats();
+        let elapsed = self.sampled_at.elapsed();
+        self.sampled_at = Instant::now();
+        self.rates = current
+            .iter()
+            .filter_map(|(name, stat)| {
+                let previous = self.previous.get(name)?;
+                Some((name.clone(), disk_io(previous, stat, elapsed)))
+            })
+            .collect();
+        for (name, io) in &self.rates {
+            let history = self.history.entry(name.clone()).or_default();
+            history.push((io.read_bytes_per_sec + io.write_bytes_per_sec) as u64);
+            if history.len() > HISTORY_LEN {
+                history.remove(0);
+            }
+        }
+        self.previous = current;
+    }
+
+    /// Rates of the disk a mount lives on. Partitions report their parent device's I/O.
+    pub fn for_disk(&self, disk: &Disk) -> Option<(&DiskIo, &[u64])> {
+        let device = parent_device(&disk.name.to_string_lossy());
+        let io = self.rates.get(&device)?;
+        let history = self.history.get(&device).map(Vec::as_slice).unwrap_or(&[]);
+        Some((io, history))
+    }
+}
+
+impl Default for DiskIoSampler {
+    fn default() -> DiskIoSampler {
+        DiskIoSampler::new()
+    }
+}
+
+fn read_diskstats() -> HashMap<String, DiskStat> {
+    diskstats()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|stat| (stat.name.clone(), stat))
+        .collect()
+}
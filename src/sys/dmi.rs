@@ -0,0 +1,156 @@
+// Hardware identification from the SMBIOS tables the kernel exposes in `/sys/class/dmi/id`,
+// or the device tree `model` on ARM boards without DMI, like the Raspberry Pi.
+
+use super::users;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HostInfo {
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    /// Only read when asked for and running as root, the file isn't world readable anyway.
+    pub serial: Option<String>,
+    pub board_vendor: Option<String>,
+    /// Mainboard model, what self-built machines are known by.
+    pub board: Option<String>,
+    pub bios_vendor: Option<String>,
+    pub bios_version: Option<String>,
+    pub bios_date: Option<String>,
+    pub chassis: Option<&'static str>,
+    /// Device tree `model`, e.g. `Raspberry Pi 4 Model B Rev 1.4`.
+    pub model: Option<String>,
+}
+
+impl HostInfo {
+    /// What the machine is, e.g. `LENOVO ThinkPad T480 (Notebook)`, or its mainboard when
+    /// the vendor didn't fill in the product.
+    pub fn description(&self) -> Option<String> {
+        let mut description = match (&self.vendor, &self.product, &self.model, &self.board) {
+            (Some(vendor), Some(product), _, _) => format!("{} {}", vendor, product),
+            (None, Some(product), _, _) => product.clone(),
+            (_, None, Some(model), _) => model.clone(),
+            // Self-built machines leave the product a placeholder but name their mainboard
+            (vendor, None, None, Some(board)) => {
+                match self.board_vendor.as_ref().or(vendor.as_ref()) {
+                    Some(vendor) => format!("{} {}", vendor, board),
+                    None => board.clone(),
+                }
+            }
+            (Some(vendor), None, None, None) => vendor.clone(),
+            (None, None, None, None) => return None,
+        };
+        if let Some(version) = &self.version {
+            description += &format!(" {}", version);
+        }
+        if let Some(chassis) = self.chassis {
+            description += &format!(" ({})", chassis);
+        }
+        Some(description)
+    }
+
+    /// BIOS/UEFI vendor, version and release date.
+    pub fn firmware(&self) -> Option<String> {
+        let version = self.bios_version.as_ref()?;
+        let mut firmware = match &self.bios_vendor {
+            Some(vendor) => format!("{} {}", vendor, version),
+            None => version.clone(),
+        };
+        if let Some(date) = &self.bios_date {
+            firmware += &format!(" ({})", date);
+        }
+        Some(firmware)
+    }
+}
+
+/// Placeholders board vendors leave in fields they don't care about.
+fn is_placeholder(value: &str) -> bool {
+    const PLACEHOLDERS: [&str; 9] = [
+        "To Be Filled By O.E.M.",
+        "To be filled by O.E.M.",
+        "Default string",
+        "System Product Name",
+        "System manufacturer",
+        "System Version",
+        "Not Specified",
+        "Not Applicable",
+        "None",
+    ];
+    value.is_empty() || PLACEHOLDERS.contains(&value)
+}
+
+/// SMBIOS chassis types (DSP0134 section 7.4.1).
+pub fn chassis_name(chassis_type: u8) -> Option<&'static str> {
+    Some(match chassis_type {
+        3 => "Desktop",
+        4 => "Low Profile Desktop",
+        5 => "Pizza Box",
+        6 => "Mini Tower",
+        7 => "Tower",
+        8 => "Portable",
+        9 => "Laptop",
+        10 => "Notebook",
+        11 => "Hand Held",
+        12 => "Docking Station",
+        13 => "All in One",
+        14 => "Sub Notebook",
+        15 => "Space-saving",
+        16 => "Lunch Box",
+        17 => "Main Server Chassis",
+        23 => "Rack Mount Chassis",
+        24 => "Sealed-case PC",
+        28 => "Blade",
+        30 => "Tablet",
+        31 => "Convertible",
+        32 => "Detachable",
+        33 => "IoT Gateway",
+        34 => "Embedded PC",
+        35 => "Mini PC",
+        36 => "Stick PC",
+        _ => return None,
+    })
+}
+
+pub fn host_info(show_serial: bool) -> HostInfo {
+    let is_root = users::euid() == 0;
+    host_info_in(
+        Path::new("/sys/class/dmi/id"),
+        Path::new("/sys/firmware/devicetree/base/model"),
+        show_serial && is_root,
+    )
+}
+
+/// Reads DMI fields below `dmi` and the device tree model at `model`.
+pub fn host_info_in(dmi: &Path, model: &Path, read_serial: bool) -> HostInfo {
+    let read = |path: PathBuf| {
+        // Device tree strings are NUL terminated
+        let value = fs::read_to_string(path).ok()?;
+        let value = value.trim_end_matches('\0').trim();
+        if is_placeholder(value) {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    };
+    HostInfo {
+        vendor: read(dmi.join("sys_vendor")),
+        product: read(dmi.join("product_name")),
+        version: read(dmi.join("product_version")),
+        serial: if read_serial {
+            read(dmi.join("product_serial"))
+        } else {
+            None
+        },
+        board_vendor: read(dmi.join("board_vendor")),
+        board: read(dmi.join("board_name")),
+        bios_vendor: read(dmi.join("bios_vendor")),
+        bios_version: read(dmi.join("bios_version")),
+        bios_date: read(dmi.join("bios_date")),
+        chassis: read(dmi.join("chassis_type"))
+            .and_then(|chassis_type| chassis_name(chassis_type.parse().ok()?)),
+        model: read(model.to_path_buf()),
+    }
+}
@@ -15,6 +15,19 @@ pub enum MemUnit {
 }
 
 impl MemUnit {
+    /// Picks the largest decimal unit that keeps at least one whole unit.
+    pub fn from_bytes(bytes: u64) -> MemUnit {
+        let bytes_f = bytes as f64;
+        match bytes {
+            0..=999 => MemUnit::B(bytes_f),
+            1_000..=999_999 => MemUnit::KB(bytes_f),
+            1_000_000..=999_999_999 => MemUnit::MB(bytes_f),
+            1_000_000_000..=999_999_999_999 => MemUnit::GB(bytes_f),
+            1_000_000_000_000..=999_999_999_999_999 => MemUnit::TB(bytes_f),
+            _ => MemUnit::PB(bytes_f),
+        }
+    }
+
     fn as_str(&self) -> &'static str {
         match self {
             MemUnit::B(_) => "B",
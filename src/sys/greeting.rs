@@ -0,0 +1,174 @@
+// The header greeting, rendered from a template like
+// `Good {time_of_day}, {name}! Welcome to {os} on {host}.` so front ends can style each
+// placeholder on its own. Unknown placeholders are left as they are.
+
+use super::{distro, format_timestamp, hostname, local_hour, logins::LastLogin, users, AnsiColor};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const DEFAULT_TEMPLATE: &str =
+    "Good {time_of_day}, {name}! Welcome to {os} on {host}.\nLast login: {last_login}";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Field {
+    /// `morning`, `afternoon` or `evening`.
+    TimeOfDay,
+    /// Full name from gecos, or the login name when there's none.
+    Name,
+    User,
+    Host,
+    /// `PRETTY_NAME` from os-release, with the variant and build ID.
+    Os,
+    LastLogin,
+}
+
+impl Field {
+    pub fn from_name(name: &str) -> Option<Field> {
+        Some(match name {
+            "time_of_day" => Field::TimeOfDay,
+            "name" => Field::Name,
+            "user" => Field::User,
+            "host" => Field::Host,
+            "os" => Field::Os,
+            "last_login" => Field::LastLogin,
+            _ => return None,
+        })
+    }
+}
+
+/// How a placeholder looks, in terms both front ends can show.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FieldStyle {
+    /// `None` keeps the front end's colour for the field.
+    pub color: Option<AnsiColor>,
+    pub bold: bool,
+}
+
+/// The header greeting's template, with styles for placeholders that shouldn't look the
+/// way the front end has them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Greeting {
+    pub template: String,
+    pub fields: HashMap<Field, FieldStyle>,
+}
+
+impl Default for Greeting {
+    fn default() -> Greeting {
+        Greeting::new(DEFAULT_TEMPLATE)
+    }
+}
+
+impl Greeting {
+    pub fn new(template: &str) -> Greeting {
+        Greeting {
+            template: template.to_string(),
+            fields: HashMap::new(),
+        }
+    }
+
+    pub fn style(&self, field: Field) -> Option<FieldStyle> {
+        self.fields.get(&field).copied()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GreetingFacts {
+    pub time_of_day: String,
+    pub name: String,
+    pub user: String,
+    pub host: String,
+    pub os: String,
+    pub last_login: Option<String>,
+}
+
+impl GreetingFacts {
+    pub fn gather(last_login: Option<&LastLogin>) -> GreetingFacts {
+        let user = users::current_user();
+        let login = user
+            .as_ref()
+            .map(|user| user.name.clone())
+            .unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        GreetingFacts {
+            time_of_day: local_hour(now)
+                .map(time_of_day)
+                .unwrap_or("day")
+                .to_string(),
+            name: user
+                .as_ref()
+                .and_then(|user| user.full_name())
+                .map(str::to_string)
+                .unwrap_or_else(|| login.clone()),
+            user: login,
+            host: hostname().to_string_lossy().into_owned(),
+            os: distro(),
+            last_login: last_login.map(|last| {
+                format!(
+                    "{} from {}",
+                    format_timestamp(last.time),
+                    if last.host.is_empty() {
+                        &last.tty
+                    } else {
+                        &last.host
+                    }
+                )
+            }),
+        }
+    }
+
+    pub fn value(&self, field: Field) -> &str {
+        match field {
+            Field::TimeOfDay => &self.time_of_day,
+            Field::Name => &self.name,
+            Field::User => &self.user,
+            Field::Host => &self.host,
+            Field::Os => &self.os,
+            Field::LastLogin => self.last_login.as_deref().unwrap_or("never"),
+        }
+    }
+}
+
+/// `morning` from 5 to 11, `afternoon` until 17 and `evening` otherwise.
+pub fn time_of_day(hour: u32) -> &'static str {
+    match hour {
+        5..=11 => "morning",
+        12..=17 => "afternoon",
+        _ => "evening",
+    }
+}
+
+/// Splits `template` into literal text and filled-in fields, in order.
+pub fn render(template: &str, facts: &GreetingFacts) -> Vec<(Option<Field>, String)> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let field = rest[start + 1..].find('}').and_then(|end| {
+            Field::from_name(&rest[start + 1..start + 1 + end]).map(|field| (field, end))
+        });
+        match field {
+            Some((field, end)) => {
+                text.push_str(&rest[..start]);
+                if !text.is_empty() {
+                    parts.push((None, std::mem::take(&mut text)));
+                }
+                parts.push((Some(field), facts.value(field).to_string()));
+                rest = &rest[start + end + 2..];
+            }
+            None => {
+                text.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        parts.push((None, text));
+    }
+    parts
+}
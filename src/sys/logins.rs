@@ -0,0 +1,88 @@
+use super::{
+    sessions, username,
+    utmp::{read_utmp, UtmpEntry, UtmpType},
+};
+use std::collections::HashMap;
+
+/// The user's previous login, as recorded in `/var/log/wtmp`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LastLogin {
+    pub tty: String,
+    /// Remote host, empty for local logins.
+    pub host: String,
+    /// Seconds since the epoch.
+    pub time: u64,
+}
+
+/// Failed login attempts recorded in `/var/log/btmp`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FailedLogins {
+    pub count: usize,
+    /// Source addresses with the most attempts first.
+    pub top_sources: Vec<(String, usize)>,
+}
+
+pub struct LoginSummary {
+    pub last_login: Option<LastLogin>,
+    /// `None` when `/var/log/btmp` can't be read, which is usually the case unless run as root.
+    pub failed: Option<FailedLogins>,
+}
+
+pub fn login_summary() -> LoginSummary {
+    let user = username();
+    // Our own session is the newest wtmp entry, so only look at logins before it.
+    let before = sessions()
+        .into_iter()
+        .filter(|session| session.current)
+        .map(|session| session.login_time)
+        .min();
+    let wtmp = read_utmp("/var/log/wtmp").unwrap_or_default();
+    let last_login = last_login_from(&wtmp, &user, before);
+    let failed = read_utmp("/var/log/btmp")
+        .ok()
+        .map(|btmp| failed_logins_from(&btmp, last_login.as_ref().map(|l| l.time), 3));
+    LoginSummary { last_login, failed }
+}
+
+/// Finds the latest login of `user` strictly before `before` (or the latest overall).
+pub fn last_login_from(
+    entries: &[UtmpEntry],
+    user: &str,
+    before: Option<u64>,
+) -> Option<LastLogin> {
+    entries
+        .iter()
+        .filter(|entry| entry.type_ == UtmpType::UserProcess && entry.user == user)
+        .filter(|entry| match before {
+            Some(before) => entry.time < before,
+            None => true,
+        })
+        .max_by_key(|entry| entry.time)
+        .map(|entry| LastLogin {
+            tty: entry.line.clone(),
+            host: entry.host.clone(),
+            time: entry.time,
+        })
+}
+
+/// Counts failed attempts after `since` and keeps the `top` busiest sources.
+pub fn failed_logins_from(entries: &[UtmpEntry], since: Option<u64>, top: usize) -> FailedLogins {
+    let mut sources: HashMap<String, usize> = HashMap::new();
+    let mut count = 0;
+    for entry in entries.iter().filter(|entry| match since {
+        Some(since) => entry.time > since,
+        None => true,
+    }) {
+        count += 1;
+        let source = match entry.addr {
+            Some(addr) => addr.to_string(),
+            None if !entry.host.is_empty() => entry.host.clone(),
+            None => "local".to_string(),
+        };
+        *sources.entry(source).or_insert(0) += 1;
+    }
+    let mut top_sources: Vec<(String, usize)> = sources.into_iter().collect();
+    top_sources.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top_sources.truncate(top);
+    FailedLogins { count, top_sources }
+}
@@ -0,0 +1,163 @@
+// Linux software RAID (md) arrays, from `/proc/mdstat`.
+
+use std::{fmt, fs};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdMember {
+    /// Block device name, e.g. `sda1`.
+    pub name: String,
+    /// Marked `(F)`: the kernel kicked it out of the array.
+    pub faulty: bool,
+    /// Marked `(S)`.
+    pub spare: bool,
+}
+
+/// A resync, recovery (rebuild), reshape or check running on an array.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdSync {
+    /// `resync`, `recovery`, `reshape` or `check`.
+    pub action: String,
+    pub percent: f64,
+    /// Minutes left, as estimated by the kernel.
+    pub finish_minutes: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MdArray {
+    pub name: String,
+    pub active: bool,
+    /// `raid1`, `raid5`, `raid10`, ... Empty for inactive arrays.
+    pub level: String,
+    pub members: Vec<MdMember>,
+    /// Devices the array should have, the `n` of `[n/m]`.
+    pub devices_wanted: usize,
+    /// Devices in use, the `m` of `[n/m]`.
+    pub devices_up: usize,
+    pub sync: Option<MdSync>,
+}
+
+impl MdArray {
+    pub fn is_degraded(&self) -> bool {
+        self.devices_up < self.devices_wanted || self.members.iter().any(|member| member.faulty)
+    }
+
+    pub fn failed_members(&self) -> Vec<&str> {
+        self.members
+            .iter()
+            .filter(|member| member.faulty)
+            .map(|member| member.name.as_str())
+            .collect()
+    }
+}
+
+impl fmt::Display for MdArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.active {
+            return write!(f, "{} inactive", self.name);
+        }
+        write!(
+            f,
+            "{} {}, {} of {} devices up",
+            self.name, self.level, self.devices_up, self.devices_wanted
+        )?;
+        let failed = self.failed_members();
+        if !failed.is_empty() {
+            write!(f, ", {} failed", failed.join(", "))?;
+        }
+        if let Some(sync) = &self.sync {
+            write!(f, ", {} {:.1}% done", sync.action, sync.percent)?;
+            if let Some(minutes) = sync.finish_minutes {
+                write!(f, " ({:.0} min left)", minutes)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn md_arrays() -> Vec<MdArray> {
+    fs::read_to_string("/proc/mdstat")
+        .map(|content| parse_mdstat(&content))
+        .unwrap_or_default()
+}
+
+fn parse_member(member: &str) -> MdMember {
+    // `sda1[0]`, `sdd1[3](F)` or `sdb[2](S)`
+    let name = member.split('[').next().unwrap_or(member);
+    MdMember {
+        name: name.to_string(),
+        faulty: member.ends_with("(F)"),
+        spare: member.ends_with("(S)"),
+    }
+}
+
+/// `[==>....]  recovery = 12.6% (123456/976630272) finish=127.5min speed=111583K/sec`
+fn parse_sync(line: &str) -> Option<MdSync> {
+    let mut fields = line
+        .split_whitespace()
+        .skip_while(|field| field.starts_with('['));
+    let action = fields.next()?.to_string();
+    if fields.next()? != "=" {
+        return None;
+    }
+    let percent = fields.next()?.strip_suffix('%')?.parse().ok()?;
+    let finish_minutes = fields
+        .find_map(|field| field.strip_prefix("finish="))
+        .and_then(|finish| finish.strip_suffix("min"))
+        .and_then(|minutes| minutes.parse().ok());
+    Some(MdSync {
+        action,
+        percent,
+        finish_minutes,
+    })
+}
+
+pub fn parse_mdstat(content: &str) -> Vec<MdArray> {
+    let mut arrays: Vec<MdArray> = Vec::new();
+    for line in content.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            // `md0 : active raid5 sdc1[2] sdb1[1] sda1[0]`
+            let (name, rest) = match line.split_once(" : ") {
+                Some((name, rest)) if name.starts_with("md") => (name, rest),
+                _ => continue,
+            };
+            let mut fields = rest.split_whitespace().peekable();
+            let active = fields.next() == Some("active");
+            // Arrays switched to read-only show `(read-only)` or `(auto-read-only)` here
+            if let Some(field) = fields.peek() {
+                if field.starts_with('(') {
+                    fields.next();
+                }
+            }
+            let level = if active {
+                fields.next().unwrap_or_default().to_string()
+            } else {
+                String::new()
+            };
+            arrays.push(MdArray {
+                name: name.to_string(),
+                active,
+                level,
+                members: fields.map(parse_member).collect(),
+                ..MdArray::default()
+            });
+            continue;
+        }
+        let array = match arrays.last_mut() {
+            Some(array) => array,
+            None => continue,
+        };
+        let line = line.trim();
+        if let Some(sync) = parse_sync(line) {
+            array.sync = Some(sync);
+        } else if let Some((wanted, up)) = line
+            .split_whitespace()
+            .filter_map(|field| field.strip_prefix('[')?.strip_suffix(']')?.split_once('/'))
+            .find_map(|(wanted, up)| Some((wanted.parse().ok()?, up.parse().ok()?)))
+        {
+            // `976630464 blocks super 1.2 [2/2] [UU]`
+            array.devices_wanted = wanted;
+            array.devices_up = up;
+        }
+    }
+    arrays
+}
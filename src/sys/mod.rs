@@ -5,32 +5,114 @@ use std::{
     io::{self, Read},
 };
 
+pub mod btrfs;
 pub mod disks;
+pub mod diskstats;
+pub mod dmi;
 pub mod format_num;
+pub mod greeting;
 pub mod hostname;
+pub mod logins;
+pub mod mdstat;
+pub mod net;
 pub mod os_release;
+pub mod power;
+pub mod pressure;
 pub mod process;
+pub mod reboot;
+pub mod sessions;
+pub mod smart;
+pub mod top;
+pub mod updates;
 pub mod uptime;
+pub mod users;
+pub mod utmp;
+pub mod virt;
+pub mod zfs;
+pub mod zram;
 
-pub use self::disks::get_all_disks;
+pub use self::btrfs::btrfs_filesystems;
+pub use self::disks::{get_all_disks, get_disks, DiskFilter, DiskRule, DiskThresholds};
+pub use self::diskstats::DiskIoSampler;
+pub use self::dmi::host_info;
 pub use self::format_num::MemUnit;
+pub use self::greeting::GreetingFacts;
 pub use self::hostname::hostname;
-pub use self::os_release::{distro, OsRelease};
-pub use self::process::{process_by_user, username};
-pub use self::uptime::{format_duration, get_uptime, uptime};
+pub use self::logins::login_summary;
+pub use self::mdstat::md_arrays;
+pub use self::net::{connections_by_remote, listening_ports};
+pub use self::os_release::{distro, AnsiColor, OsRelease};
+pub use self::power::power_status;
+pub use self::pressure::pressure;
+pub use self::process::{process_by_user, BlockedWatch};
+pub use self::reboot::reboot_required;
+pub use self::sessions::sessions;
+pub use self::smart::{smart_health, SmartMonitor};
+pub use self::top::{ProcessSampler, ProcessSort};
+pub use self::updates::pending_updates;
+pub use self::uptime::{
+    format_duration, format_idle, format_timestamp, get_uptime, local_hour, uptime,
+};
+pub use self::users::{current_user, username};
+pub use self::virt::{virt, Virt};
+pub use self::zfs::zfs_pools;
+pub use self::zram::zram_devices;
 
 // https://github.com/FillZpp/sys-info-rs
 // System memory information.
+// All sizes are in KiB, as reported by `/proc/meminfo`.
 #[derive(Debug)]
 pub struct MemInfo {
     /// Total physical memory.
     pub total: u64,
     pub free: u64,
+    /// Estimate of memory available for new applications without swapping.
+    pub available: u64,
     pub cached: u64,
     pub buffers: u64,
+    /// Shared memory, including tmpfs.
+    pub shared: u64,
+    /// Kernel slab allocations, reclaimable or not.
+    pub slab: u64,
     pub sreclaimable: u64,
+    /// Memory waiting to be written back to disk.
+    pub dirty: u64,
     pub swap_total: u64,
     pub swap_free: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size: u64,
+    /// Memory used by the zswap pool, zero when zswap is off or the kernel doesn't report it.
+    pub zswap: u64,
+    /// Swapped out data held in the zswap pool, uncompressed.
+    pub zswapped: u64,
+}
+
+impl MemInfo {
+    pub fn used(&self) -> u64 {
+        self.total
+            .saturating_sub(self.free + self.cached + self.buffers + self.sreclaimable)
+    }
+
+    pub fn used_percent(&self) -> f64 {
+        percent(self.used(), self.total)
+    }
+
+    pub fn swap_used(&self) -> u64 {
+        self.swap_total.saturating_sub(self.swap_free)
+    }
+
+    pub fn swap_used_percent(&self) -> f64 {
+        percent(self.swap_used(), self.swap_total)
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100_f64
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -55,6 +137,10 @@ pub struct LoadAvg {
 pub fn mem_info() -> Result<MemInfo, Error> {
     let mut s = String::new();
     File::open("/proc/meminfo")?.read_to_string(&mut s)?;
+    parse_meminfo(&s)
+}
+
+pub fn parse_meminfo(s: &str) -> Result<MemInfo, Error> {
     let mut meminfo_hashmap = HashMap::new();
     for line in s.lines() {
         let mut split_line = line.split_whitespace();
@@ -78,14 +164,31 @@ pub fn mem_info() -> Result<MemInfo, Error> {
             meminfo_hashmap.insert(label, value);
         }
     }
+    let optional = |label| meminfo_hashmap.get(label).copied().unwrap_or(0);
+    let free = *meminfo_hashmap.get("MemFree").ok_or(Error::Unknown)?;
+    let cached = *meminfo_hashmap.get("Cached").ok_or(Error::Unknown)?;
+    let buffers = *meminfo_hashmap.get("Buffers").ok_or(Error::Unknown)?;
     Ok(MemInfo {
         total: *meminfo_hashmap.get("MemTotal").ok_or(Error::Unknown)?,
-        free: *meminfo_hashmap.get("MemFree").ok_or(Error::Unknown)?,
-        cached: *meminfo_hashmap.get("Cached").ok_or(Error::Unknown)?,
-        buffers: *meminfo_hashmap.get("Buffers").ok_or(Error::Unknown)?,
+        free,
+        // MemAvailable only exists since Linux 3.14
+        available: meminfo_hashmap
+            .get("MemAvailable")
+            .copied()
+            .unwrap_or(free + cached + buffers),
+        cached,
+        buffers,
+        shared: optional("Shmem"),
+        slab: optional("Slab"),
         sreclaimable: *meminfo_hashmap.get("SReclaimable").ok_or(Error::Unknown)?,
+        dirty: optional("Dirty"),
         swap_total: *meminfo_hashmap.get("SwapTotal").ok_or(Error::Unknown)?,
         swap_free: *meminfo_hashmap.get("SwapFree").ok_or(Error::Unknown)?,
+        hugepages_total: optional("HugePages_Total"),
+        hugepages_free: optional("HugePages_Free"),
+        hugepage_size: optional("Hugepagesize"),
+        zswap: optional("Zswap"),
+        zswapped: optional("Zswapped"),
     })
 }
 
@@ -243,7 +346,10 @@ impl From<Box<dyn std::error::Error>> for Error {
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::{collections::BTreeMap, env, iter::FromIterator, process::Command, time::Duration};
+    use std::{
+        collections::BTreeMap, env, iter::FromIterator, path::PathBuf, process::Command,
+        time::Duration,
+    };
 
     const EXAMPLE_OSRELEASE: &str = r#"NAME="Pop!_OS"
     VERSION="18.04 LTS"
@@ -258,6 +364,221 @@ mod test {
     VERSION_CODENAME=bionic
     EXTRA_KEY=thing
     ANOTHER_KEY="#;
+    const EXAMPLE_DPKG_STATUS: &str = "Package: bash
+Status: install ok installed
+Architecture: amd64
+Version: 5.0-6ubuntu1
+
+Package: openssl
+Status: install ok installed
+Architecture: amd64
+Version: 1.1.1f-1ubuntu2
+
+Package: tzdata
+Status: install ok installed
+Architecture: all
+Version: 2019c-3ubuntu1
+
+Package: removed-thing
+Status: deinstall ok config-files
+Architecture: amd64
+Version: 1.0-1
+";
+    const EXAMPLE_APT_PACKAGES: &str = "Package: bash
+Architecture: amd64
+Version: 5.0-6ubuntu1
+
+Package: openssl
+Architecture: amd64
+Version: 1.1.1f-1ubuntu2.1
+
+Package: tzdata
+Architecture: all
+Version: 2020a-0ubuntu0.20.04
+
+Package: removed-thing
+Architecture: amd64
+Version: 2.0-1
+";
+    const EXAMPLE_APT_SECURITY_PACKAGES: &str = "Package: openssl
+Architecture: amd64
+Version: 1.1.1f-1ubuntu2.1
+";
+    const EXAMPLE_MOUNTINFO: &str = "22 1 254:1 / / rw,relatime shared:1 - ext4 /dev/vda1 rw
+23 22 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+24 22 0:5 / /dev rw,nosuid,relatime shared:2 - devtmpfs udev rw,size=4038016k
+25 22 202:17 / /srv/My\\040Files rw,relatime shared:30 - xfs /dev/xvdb1 rw
+26 22 254:1 /var/lib/docker /mnt/docker rw,relatime shared:1 - ext4 /dev/vda1 rw
+27 22 0:45 / /tank/home rw,xattr,noacl shared:40 - zfs tank/home rw
+28 22 0:50 / /mnt/share rw,relatime shared:45 - nfs4 nas:/export rw,vers=4.2
+29 22 7:0 / /snap/core/9066 ro,nodev,relatime shared:50 - squashfs /dev/loop0 ro
+30 22 0:55 /@home /home rw,relatime shared:60 - btrfs /dev/nvme0n1p2 rw,subvol=/@home
+31 22 0:55 /@snapshots /.snapshots rw,relatime shared:61 - btrfs /dev/nvme0n1p2 rw,subvol=/@snapshots
+32 22 0:55 /@home/alice/vm /var/lib/vm rw,relatime shared:60 - btrfs /dev/nvme0n1p2 rw,subvol=/@home";
+    const EXAMPLE_ZPOOL_LIST: &str =
+        "tank\t7971459301376\t3565216071680\t4406243229696\t12%\t44\tONLINE
+backup\t3985729650688\t3587156685619\t398572965069\t-\t90\tDEGRADED
+";
+    const EXAMPLE_ZPOOL_STATUS: &str = "  pool: backup
+ state: DEGRADED
+status: One or more devices could not be used because the label is missing or
+\tinvalid.  Sufficient replicas exist for the pool to continue
+\tfunctioning in a degraded state.
+action: Replace the device using 'zpool replace'.
+  scan: resilver in progress since Sun Apr 12 10:14:01 2020
+\t1.20T scanned at 402M/s, 650G issued at 213M/s, 3.26T total
+\t325G resilvered, 19.48% done, 0 days 03:34:08 to go
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\tbackup      DEGRADED     0     0     0
+\t  mirror-0  DEGRADED     0     0     0
+\t    sdc     ONLINE       0     0     0
+\t    sdd     UNAVAIL      0     0     0
+
+errors: No known data errors
+
+  pool: tank
+ state: ONLINE
+  scan: scrub repaired 0B in 0 days 02:10:12 with 0 errors on Sun Apr 12 02:34:13 2020
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        ONLINE       0     0     0
+\t  raidz1-0  ONLINE       0     0     0
+\t    sda     ONLINE       0     0     0
+\t    sdb     ONLINE       0     0     0
+
+errors: No known data errors
+";
+    const EXAMPLE_MDSTAT: &str = "Personalities : [raid1] [raid6] [raid5] [raid4]
+md1 : active raid1 sdb2[1] sda2[0]
+      976630464 blocks super 1.2 [2/2] [UU]
+      bitmap: 1/8 pages [4KB], 65536KB chunk
+
+md0 : active raid5 sdd1[3](F) sdc1[2] sdb1[1] sda1[0]
+      1953260544 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+      [==>..................]  recovery = 12.6% (123456/976630272) finish=127.5min speed=111583K/sec
+
+md2 : active (auto-read-only) raid1 sde1[0] sdf1[1](S)
+      1048512 blocks [1/1] [U]
+
+md127 : inactive sdg[0](S)
+      976631512 blocks super 1.2
+
+unused devices: <none>
+";
+    const EXAMPLE_SMARTCTL_ATA: &str = r#"{
+  "json_format_version": [1, 0],
+  "smartctl": {"version": [7, 1], "exit_status": 0},
+  "device": {"name": "/dev/sda", "info_name": "/dev/sda [SAT]", "type": "sat", "protocol": "ATA"},
+  "model_family": "Western Digital Red",
+  "model_name": "WDC WD40EFRX-68N32N0",
+  "serial_number": "WD-WCC7K1234567",
+  "rotation_rate": 5400,
+  "smart_status": {"passed": true},
+  "ata_smart_attributes": {
+    "revision": 16,
+    "table": [
+      {"id": 1, "name": "Raw_Read_Error_Rate", "value": 200, "worst": 200, "thresh": 51, "raw": {"value": 0, "string": "0"}},
+      {"id": 5, "name": "Reallocated_Sector_Ct", "value": 200, "worst": 200, "thresh": 140, "raw": {"value": 8, "string": "8"}},
+      {"id": 9, "name": "Power_On_Hours", "value": 62, "worst": 62, "thresh": 0, "raw": {"value": 28021, "string": "28021"}},
+      {"id": 194, "name": "Temperature_Celsius", "value": 114, "worst": 103, "thresh": 0, "raw": {"value": 36, "string": "36"}}
+    ]
+  },
+  "power_on_time": {"hours": 28021},
+  "power_cycle_count": 97,
+  "temperature": {"current": 36}
+}"#;
+    const EXAMPLE_SMARTCTL_NVME: &str = r#"{
+  "json_format_version": [1, 0],
+  "smartctl": {"version": [7, 1], "exit_status": 0},
+  "device": {"name": "/dev/nvme0", "info_name": "/dev/nvme0", "type": "nvme", "protocol": "NVMe"},
+  "model_name": "Samsung SSD 970 EVO Plus 1TB",
+  "serial_number": "S4EWNX0N123456",
+  "smart_status": {"passed": true},
+  "nvme_smart_health_information_log": {
+    "critical_warning": 0,
+    "temperature": 41,
+    "available_spare": 100,
+    "available_spare_threshold": 10,
+    "percentage_used": 3,
+    "data_units_read": 12095283,
+    "data_units_written": 25361417,
+    "power_cycles": 1291,
+    "power_on_hours": 4410,
+    "unsafe_shutdowns": 61,
+    "media_errors": 0
+  },
+  "temperature": {"current": 41},
+  "power_cycle_count": 1291,
+  "power_on_time": {"hours": 4410}
+}"#;
+    const EXAMPLE_SMARTCTL_DENIED: &str = r#"{
+  "json_format_version": [1, 0],
+  "smartctl": {
+    "version": [7, 1],
+    "messages": [{"string": "Smartctl open device: /dev/sda failed: Permission denied", "severity": "error"}],
+    "exit_status": 2
+  }
+}"#;
+    const EXAMPLE_SMARTCTL_STANDBY: &str = r#"{
+  "json_format_version": [1, 0],
+  "smartctl": {
+    "version": [7, 1],
+    "messages": [{"string": "Device is in STANDBY mode, exit(2)", "severity": "information"}],
+    "exit_status": 2
+  }
+}"#;
+    const EXAMPLE_SMARTD_STATE: &str = "temperature-min = 21
+temperature-max = 44
+self-test-errors = 0
+ata-smart-attribute.0.id = 1
+ata-smart-attribute.0.val = 200
+ata-smart-attribute.0.raw = 0
+ata-smart-attribute.1.id = 5
+ata-smart-attribute.1.val = 200
+ata-smart-attribute.1.raw = 0
+ata-smart-attribute.2.id = 9
+ata-smart-attribute.2.val = 62
+ata-smart-attribute.2.raw = 28021
+ata-smart-attribute.3.id = 194
+ata-smart-attribute.3.val = 114
+ata-smart-attribute.3.raw = 188978561060
+";
+    const EXAMPLE_MEMINFO: &str = "MemTotal:       16303428 kB
+MemFree:         1034560 kB
+MemAvailable:    9618900 kB
+Buffers:          402316 kB
+Cached:          8210968 kB
+SwapCached:         1024 kB
+Shmem:            615824 kB
+Slab:             912004 kB
+SReclaimable:     704284 kB
+SwapTotal:       2097148 kB
+SwapFree:        1572860 kB
+Zswap:             10240 kB
+Zswapped:          40960 kB
+Dirty:               428 kB
+HugePages_Total:       4
+HugePages_Free:        3
+Hugepagesize:       2048 kB";
+    const EXAMPLE_PROC_MAPS: &str = "55d4c8a2e000-55d4c8a5c000 r--p 00000000 fd:01 1838089                    /usr/sbin/sshd
+7f0b3c2a1000-7f0b3c2c3000 r--p 00000000 fd:01 1835328                    /usr/lib/x86_64-linux-gnu/libc-2.31.so (deleted)
+7f0b3c2c3000-7f0b3c43b000 r-xp 00022000 fd:01 1835328                    /usr/lib/x86_64-linux-gnu/libc-2.31.so (deleted)
+7f0b3c4a0000-7f0b3c4a4000 rw-s 00000000 00:05 98311                      /SYSV00000000 (deleted)
+7f0b3c4b0000-7f0b3c4b4000 rw-s 00000000 00:01 1027                       /memfd:pulseaudio (deleted)
+7f0b3c4c0000-7f0b3c4e4000 r--p 00000000 fd:01 1835400                    /usr/lib/x86_64-linux-gnu/libssl.so.1.1 (deleted)
+7f0b3c4f0000-7f0b3c4f4000 rw-p 00000000 00:00 0
+7ffd5a1d3000-7ffd5a1f4000 rw-p 00000000 00:00 0                          [stack]";
+    const EXAMPLE_PROC_NET_TCP: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21341 1 0000000000000000 100 0 0 10 0
+   1: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 18790 1 0000000000000000 100 0 0 10 0
+   2: 0F02000A:0016 0202000A:D5A8 01 00000000:00000000 02:000A7C1E 00000000     0        0 40021 2 0000000000000000 20 4 29 10 -1
+   3: 0F02000A:0016 0202000A:D5AA 01 00000000:00000000 02:000A7C1E 00000000     0        0 40022 2 0000000000000000 20 4 29 10 -1
+   4: 0F02000A:9C40 0A01A8C0:01BB 01 00000000:00000000 02:000A7C1E 00000000  1000        0 40023 2 0000000000000000 20 4 29 10 -1";
+    const EXAMPLE_PROC_NET_UDP6: &str = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  123: 00000000000000000000000000000000:14E9 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   104        0 17211 2 0000000000000000 0";
     #[test]
     pub fn test_loadavg() {
         let load = loadavg().unwrap();
@@ -334,6 +655,11 @@ mod test {
                 bug_report_url: "https://github.com/pop-os/pop/issues".into(),
                 privacy_policy_url: "https://system76.com/privacy".into(),
                 version_codename: "bionic".into(),
+                variant: "".into(),
+                variant_id: "".into(),
+                build_id: "".into(),
+                ansi_color: "".into(),
+                logo: "".into(),
                 extra: {
                     let mut map = BTreeMap::new();
                     map.insert("EXTRA_KEY".to_owned(), "thing".to_owned());
@@ -490,4 +816,1011 @@ mod test {
         let disk = get_all_disks();
         assert!(!disk.is_empty())
     }
+
+    #[test]
+    fn test_listening_ports() {
+        use procfs::net::{read_tcp_table, read_udp_table};
+        use std::io::BufReader;
+
+        let tcp = read_tcp_table(BufReader::new(EXAMPLE_PROC_NET_TCP.as_bytes())).unwrap();
+        let udp = read_udp_table(BufReader::new(EXAMPLE_PROC_NET_UDP6.as_bytes())).unwrap();
+        let mut owners = HashMap::new();
+        owners.insert(18790, "sshd".to_string());
+        let ports = net::listening_from(&tcp, &udp, &owners);
+
+        assert_eq!(ports.len(), 3);
+        assert_eq!(ports[0].protocol, net::Protocol::Tcp);
+        assert_eq!(ports[0].address, "0.0.0.0:22".parse().unwrap());
+        assert_eq!(ports[0].process, Some("sshd".to_string()));
+        assert_eq!(ports[1].address, "127.0.0.1:631".parse().unwrap());
+        assert_eq!(ports[1].process, None);
+        assert_eq!(ports[2].protocol, net::Protocol::Udp);
+        assert_eq!(ports[2].address, "[::]:5353".parse().unwrap());
+    }
+
+    #[test]
+    fn test_connections_by_remote() {
+        use procfs::net::read_tcp_table;
+        use std::io::BufReader;
+
+        let tcp = read_tcp_table(BufReader::new(EXAMPLE_PROC_NET_TCP.as_bytes())).unwrap();
+        let connections = net::connections_from(&tcp);
+
+        assert_eq!(connections.len(), 2);
+        assert_eq!(
+            connections[0].address,
+            "10.0.2.2".parse::<std::net::IpAddr>().unwrap()
+        );
+        assert_eq!(connections[0].count, 2);
+        assert_eq!(
+            connections[1].address,
+            "192.168.1.10".parse::<std::net::IpAddr>().unwrap()
+        );
+        assert_eq!(connections[1].count, 1);
+    }
+
+    fn utmp_record(type_: i16, line: &str, user: &str, host: &str, time: i32) -> Vec<u8> {
+        let mut record = vec![0u8; utmp::UTMP_RECORD_SIZE];
+        record[0..2].copy_from_slice(&type_.to_ne_bytes());
+        record[4..8].copy_from_slice(&1234i32.to_ne_bytes());
+        record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+        record[340..344].copy_from_slice(&time.to_ne_bytes());
+        if let Ok(ip) = host.parse::<std::net::Ipv4Addr>() {
+            record[348..352].copy_from_slice(&ip.octets());
+        }
+        record
+    }
+
+    #[test]
+    fn test_parse_utmp() {
+        let mut data = utmp_record(2, "~", "reboot", "", 1_583_000_000);
+        data.extend(utmp_record(
+            7,
+            "pts/0",
+            "wolf",
+            "192.168.1.10",
+            1_583_312_400,
+        ));
+        data.extend(utmp_record(8, "pts/1", "", "", 1_583_312_500));
+        // Trailing partial records are ignored
+        data.extend(&[0u8; 17]);
+        let entries = utmp::parse_utmp(&data);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].type_, utmp::UtmpType::BootTime);
+        assert_eq!(entries[1].type_, utmp::UtmpType::UserProcess);
+        assert_eq!(entries[1].pid, 1234);
+        assert_eq!(entries[1].line, "pts/0");
+        assert_eq!(entries[1].user, "wolf");
+        assert_eq!(entries[1].host, "192.168.1.10");
+        assert_eq!(entries[1].time, 1_583_312_400);
+        assert_eq!(entries[1].addr, Some("192.168.1.10".parse().unwrap()));
+        assert_eq!(entries[2].addr, None);
+
+        let sessions = sessions::sessions_from_utmp(&entries);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user, "wolf");
+        assert_eq!(sessions[0].tty, "pts/0");
+    }
+
+    #[test]
+    fn test_format_idle() {
+        assert_eq!(format_idle(Duration::from_secs(42)), "42s");
+        assert_eq!(format_idle(Duration::from_secs(600)), "10m");
+        assert_eq!(format_idle(Duration::from_secs(7_500)), "2:05");
+        assert_eq!(format_idle(Duration::from_secs(200_000)), "2d");
+    }
+
+    #[test]
+    fn test_logins() {
+        let mut wtmp = utmp_record(7, "pts/0", "wolf", "10.0.0.5", 1_000);
+        wtmp.extend(utmp_record(7, "tty1", "wolf", "", 2_000));
+        wtmp.extend(utmp_record(7, "pts/1", "other", "10.0.0.9", 2_500));
+        wtmp.extend(utmp_record(7, "pts/2", "wolf", "10.0.0.6", 3_000));
+        let wtmp = utmp::parse_utmp(&wtmp);
+
+        let last = logins::last_login_from(&wtmp, "wolf", Some(3_000)).unwrap();
+        assert_eq!(last.tty, "tty1");
+        assert_eq!(last.time, 2_000);
+        let last = logins::last_login_from(&wtmp, "wolf", None).unwrap();
+        assert_eq!(last.host, "10.0.0.6");
+        assert_eq!(logins::last_login_from(&wtmp, "nobody", None), None);
+
+        let mut btmp = utmp_record(6, "ssh:notty", "root", "203.0.113.7", 1_500);
+        btmp.extend(utmp_record(6, "ssh:notty", "admin", "203.0.113.7", 2_100));
+        btmp.extend(utmp_record(6, "ssh:notty", "root", "198.51.100.2", 2_200));
+        btmp.extend(utmp_record(6, "ssh:notty", "root", "203.0.113.7", 2_300));
+        btmp.extend(utmp_record(6, "tty2", "wolf", "", 2_400));
+        let btmp = utmp::parse_utmp(&btmp);
+
+        let failed = logins::failed_logins_from(&btmp, Some(2_000), 2);
+        assert_eq!(failed.count, 4);
+        assert_eq!(
+            failed.top_sources,
+            vec![
+                ("203.0.113.7".to_string(), 2),
+                ("198.51.100.2".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_deb_versions() {
+        use std::cmp::Ordering::*;
+        use updates::compare_deb_versions;
+
+        assert_eq!(compare_deb_versions("1.0", "1.0"), Equal);
+        assert_eq!(compare_deb_versions("1.0-1", "1.0-2"), Less);
+        assert_eq!(compare_deb_versions("1:0.9", "2.0"), Greater);
+        assert_eq!(compare_deb_versions("1.0~rc1", "1.0"), Less);
+        assert_eq!(compare_deb_versions("1.0", "1.0+b1"), Less);
+        assert_eq!(compare_deb_versions("1.10", "1.9"), Greater);
+        assert_eq!(
+            compare_deb_versions("1.1.1f-1ubuntu2.1", "1.1.1f-1ubuntu2"),
+            Greater
+        );
+        assert_eq!(
+            compare_deb_versions("2019c-3ubuntu1", "2020a-0ubuntu0.20.04"),
+            Less
+        );
+    }
+
+    #[test]
+    fn test_apt_pending() {
+        let installed: Vec<updates::DebPackage> = updates::parse_deb_packages(EXAMPLE_DPKG_STATUS)
+            .into_iter()
+            .filter(|package| package.installed)
+            .collect();
+        assert_eq!(installed.len(), 3);
+        let lists = vec![
+            (false, updates::parse_deb_packages(EXAMPLE_APT_PACKAGES)),
+            (
+                true,
+                updates::parse_deb_packages(EXAMPLE_APT_SECURITY_PACKAGES),
+            ),
+        ];
+        assert_eq!(updates::apt_pending(&installed, &lists), (2, 1));
+    }
+
+    #[test]
+    fn test_package_manager() {
+        let os_release = OsRelease::from_iter(EXAMPLE_OSRELEASE.lines().map(|x| x.into()));
+        assert_eq!(
+            updates::package_manager(&os_release),
+            Some(updates::PackageManager::Apt)
+        );
+        let manjaro = OsRelease {
+            id: "manjaro".into(),
+            id_like: "arch".into(),
+            ..OsRelease::default()
+        };
+        assert_eq!(
+            updates::package_manager(&manjaro),
+            Some(updates::PackageManager::Pacman)
+        );
+        let alma = OsRelease {
+            id: "almalinux".into(),
+            id_like: "rhel centos fedora".into(),
+            ..OsRelease::default()
+        };
+        assert_eq!(
+            updates::package_manager(&alma),
+            Some(updates::PackageManager::Dnf)
+        );
+    }
+
+    #[test]
+    fn test_parse_update_commands() {
+        assert_eq!(
+            updates::parse_pacman_updates(
+                "linux 5.6.3.arch1-1 -> 5.6.4.arch1-1\nvim 8.2.0510-1 -> 8.2.0520-1\nfoo 1-1 -> 2-1 [ignored]\n"
+            ),
+            2
+        );
+        assert_eq!(
+            updates::parse_dnf_check_update(
+                "\nkernel.x86_64    5.6.6-300.fc32    updates\nvim-minimal.x86_64    2:8.2.587-1.fc32    updates\nObsoleting Packages\nfoo.x86_64    1-1    updates\n"
+            ),
+            2
+        );
+        assert_eq!(
+            updates::parse_dnf_updateinfo(
+                "FEDORA-2020-1 Important/Sec. kernel-5.6.6-300.fc32.x86_64\nFEDORA-2020-2 bugfix vim-minimal-2:8.2.587-1.fc32.x86_64\nFEDORA-2020-3 Moderate/Sec. kernel-5.6.6-300.fc32.x86_64\n"
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn test_reboot_required() {
+        assert_eq!(
+            reboot::parse_reboot_required_pkgs("linux-image-5.4.0-26-generic\nlibc6\n\nlibc6\n"),
+            vec![
+                "libc6".to_string(),
+                "linux-image-5.4.0-26-generic".to_string()
+            ]
+        );
+        assert_eq!(
+            reboot::deleted_libraries(EXAMPLE_PROC_MAPS),
+            vec![
+                "/usr/lib/x86_64-linux-gnu/libc-2.31.so".to_string(),
+                "/usr/lib/x86_64-linux-gnu/libssl.so.1.1".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let mem = parse_meminfo(EXAMPLE_MEMINFO).unwrap();
+        assert_eq!(mem.available, 9_618_900);
+        assert_eq!(mem.shared, 615_824);
+        assert_eq!(mem.slab, 912_004);
+        assert_eq!(mem.dirty, 428);
+        assert_eq!(mem.hugepages_total, 4);
+        assert_eq!(mem.hugepage_size, 2048);
+        assert_eq!(mem.zswapped, 40_960);
+        assert_eq!(mem.used(), 5_951_300);
+        // Swap reports the used share, not the free one
+        assert_eq!(mem.swap_used(), 524_288);
+        assert_eq!(mem.swap_used_percent().round(), 25.0);
+    }
+
+    #[test]
+    fn test_parse_mm_stat() {
+        let zram = zram::parse_mm_stat(
+            "zram0",
+            "  4096000  1024000  1228800        0  1331200      128       12        0       44\n",
+        )
+        .unwrap();
+        assert_eq!(zram.orig_data_size, 4_096_000);
+        assert_eq!(zram.compr_data_size, 1_024_000);
+        assert!((zram.ratio() - 3.33).abs() < 0.01);
+        assert_eq!(zram::parse_mm_stat("zram1", ""), None);
+    }
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let mounts = disks::parse_mountinfo(EXAMPLE_MOUNTINFO);
+        assert_eq!(mounts.len(), 11);
+        assert_eq!(mounts[0].device_id, (254, 1));
+        assert_eq!(mounts[3].mount_point, PathBuf::from("/srv/My Files"));
+        assert_eq!(mounts[3].file_system, "xfs");
+        assert_eq!(mounts[3].source, "/dev/xvdb1");
+        assert_eq!(mounts[5].source, "tank/home");
+        assert_eq!(mounts[5].subvolume, None);
+        assert_eq!(mounts[9].subvolume, Some("/@snapshots".to_string()));
+        assert_eq!(disks::unescape_octal("a\\134b\\011c\\9"), "a\\b\tc\\9");
+    }
+
+    #[test]
+    fn test_filter_mounts() {
+        use disks::{filter_mounts, glob_match, parse_mountinfo, DiskRule};
+
+        let mounts = parse_mountinfo(EXAMPLE_MOUNTINFO);
+        let kept: Vec<String> = filter_mounts(mounts.clone(), &DiskFilter::default())
+            .iter()
+            .map(|mount| mount.mount_point.to_string_lossy().to_string())
+            .collect();
+        // The docker and vm bind mounts share a device and subvolume with others
+        assert_eq!(
+            kept,
+            vec![
+                "/",
+                "/srv/My Files",
+                "/tank/home",
+                "/mnt/share",
+                "/home",
+                "/.snapshots"
+            ]
+        );
+
+        let filter = DiskFilter {
+            include: vec![DiskRule::FileSystem("*".to_string())],
+            exclude: vec![
+                DiskRule::MountPoint("/proc".to_string()),
+                DiskRule::Device("/dev/*".to_string()),
+            ],
+        };
+        let kept = filter_mounts(mounts, &filter);
+        assert_eq!(kept.len(), 3);
+        assert!(kept.iter().all(|mount| !mount.source.starts_with("/dev/")));
+
+        assert!(glob_match("/dev/nvme*p?", "/dev/nvme0n1p2"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("/dev/sd?", "/dev/sda1"));
+        assert!(glob_match("*a*b", "xaxxab"));
+    }
+
+    #[test]
+    fn test_disk_warnings() {
+        use disks::{Disk, DiskType};
+        use std::ffi::OsString;
+
+        let disk = Disk {
+            type_: DiskType::SSD,
+            name: OsString::from("sda1"),
+            file_system: b"ext4".to_vec(),
+            mount_point: PathBuf::from("/"),
+            total_space: 100_000,
+            available_space: 5_000,
+            free_space: 10_000,
+            inodes_total: 1_000,
+            inodes_free: 50,
+            read_only: false,
+        };
+        assert_eq!(disk.used_space(), 90_000);
+        assert_eq!(disk.reserved_space(), 5_000);
+        // Like df, reserved blocks don't count as usable
+        assert_eq!(disk.used_percent().round(), 95.0);
+        assert_eq!(disk.inodes_used_percent(), Some(95.0));
+        assert_eq!(
+            disk.warnings(&DiskThresholds::default()),
+            vec!["/ is 95% full", "/ has used 95% of its inodes"]
+        );
+        let relaxed = DiskThresholds {
+            space: 96.0,
+            inodes: 99.0,
+        };
+        assert!(disk.warnings(&relaxed).is_empty());
+
+        let btrfs = Disk {
+            inodes_total: 0,
+            inodes_free: 0,
+            ..disk
+        };
+        assert_eq!(btrfs.inodes_used_percent(), None);
+    }
+
+    #[test]
+    fn test_mem_unit_from_bytes() {
+        assert_eq!(MemUnit::from_bytes(512).to_string(), "512B");
+        assert_eq!(MemUnit::from_bytes(4_500_000).to_string(), "4MB");
+        assert_eq!(MemUnit::from_bytes(167_000_000_000).to_string(), "167GB");
+    }
+
+    #[test]
+    fn test_trim_partition() {
+        use disks::trim_partition;
+
+        assert_eq!(trim_partition("sda1"), "sda");
+        assert_eq!(trim_partition("vdb"), "vdb");
+        assert_eq!(trim_partition("xvda12"), "xvda");
+        assert_eq!(trim_partition("nvme0n1p2"), "nvme0n1");
+        assert_eq!(trim_partition("mmcblk0p1"), "mmcblk0");
+        assert_eq!(trim_partition("nvme0n1"), "nvme0n1");
+        assert_eq!(trim_partition("mmcblk0"), "mmcblk0");
+        assert_eq!(trim_partition("loop7"), "loop7");
+        assert_eq!(trim_partition("123"), "123");
+    }
+
+    #[test]
+    fn test_disk_io() {
+        use procfs::DiskStat;
+
+        let before =
+            DiskStat::from_line("   8       0 sda 1000 0 20000 400 500 0 8000 600 0 0 0").unwrap();
+        let after =
+            DiskStat::from_line("   8       0 sda 1100 0 24000 500 600 0 10000 800 0 0 0").unwrap();
+        let io = diskstats::disk_io(&before, &after, Duration::from_secs(2));
+        assert_eq!(io.read_bytes_per_sec, 1_024_000.0);
+        assert_eq!(io.write_bytes_per_sec, 512_000.0);
+        assert_eq!(io.iops, 100.0);
+        assert_eq!(io.await_ms, Some(1.5));
+
+        let idle = diskstats::disk_io(&after, &after, Duration::from_secs(2));
+        assert_eq!(idle.iops, 0.0);
+        assert_eq!(idle.await_ms, None);
+    }
+
+    #[test]
+    fn test_parse_zpool() {
+        let mut pools = zfs::parse_zpool_list(EXAMPLE_ZPOOL_LIST);
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].name, "tank");
+        assert_eq!(pools[0].size, 7_971_459_301_376);
+        assert_eq!(pools[0].fragmentation, Some(12));
+        assert_eq!(pools[0].capacity, 44);
+        assert_eq!(pools[1].fragmentation, None);
+        assert_eq!(pools[1].state, "DEGRADED");
+
+        let status = zfs::parse_zpool_status(EXAMPLE_ZPOOL_STATUS);
+        assert_eq!(
+            status["tank"].0.as_deref(),
+            Some("scrub repaired 0B in 0 days 02:10:12 with 0 errors on Sun Apr 12 02:34:13 2020")
+        );
+        assert_eq!(
+            status["backup"].0.as_deref(),
+            Some(
+                "resilver in progress since Sun Apr 12 10:14:01 2020, \
+                 1.20T scanned at 402M/s, 650G issued at 213M/s, 3.26T total, \
+                 325G resilvered, 19.48% done, 0 days 03:34:08 to go"
+            )
+        );
+        assert_eq!(status["backup"].1.as_deref(), Some("No known data errors"));
+
+        pools[0].errors = status["tank"].1.clone();
+        assert!(pools[0].is_healthy());
+        assert_eq!(pools[1].to_string(), "backup DEGRADED, 90% of 3TB used");
+        assert!(!pools[1].is_healthy());
+    }
+
+    #[test]
+    fn test_btrfs_filesystems() {
+        let root = env::temp_dir().join(format!("nmotd-btrfs-{}", std::process::id()));
+        let fs_dir = root.join("0f6e4fd8-2c1c-4d2b-8a8c-b5e1f5a6a3e1");
+        for kind in &["data", "metadata", "system"] {
+            let dir = fs_dir.join("allocation").join(kind);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("total_bytes"), "1073741824\n").unwrap();
+            std::fs::write(dir.join("bytes_used"), "536870912\n").unwrap();
+        }
+        std::fs::create_dir_all(fs_dir.join("devices").join("sda2")).unwrap();
+        std::fs::create_dir_all(fs_dir.join("devinfo").join("1")).unwrap();
+        std::fs::write(
+            fs_dir.join("devinfo").join("1").join("error_stats"),
+            "write_errs 0\nread_errs 2\nflush_errs 0\ncorruption_errs 1\ngeneration_errs 0\n",
+        )
+        .unwrap();
+        std::fs::write(fs_dir.join("label"), "data\n").unwrap();
+        std::fs::create_dir_all(root.join("features")).unwrap();
+
+        let filesystems = btrfs::btrfs_filesystems_in(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(filesystems.len(), 1);
+        assert_eq!(filesystems[0].label, "data");
+        assert_eq!(filesystems[0].devices, vec!["sda2"]);
+        assert_eq!(filesystems[0].allocation.len(), 3);
+        assert_eq!(filesystems[0].allocation[1].kind, "metadata");
+        assert_eq!(filesystems[0].allocation[1].used, 536_870_912);
+        assert_eq!(filesystems[0].device_errors[0].read, 2);
+        assert_eq!(filesystems[0].error_count(), 3);
+        assert_eq!(
+            filesystems[0].to_string(),
+            "data (sda2), data 536MB of 1GB, metadata 536MB of 1GB, system 536MB of 1GB, 3 device errors"
+        );
+    }
+
+    #[test]
+    fn test_parse_mdstat() {
+        let arrays = mdstat::parse_mdstat(EXAMPLE_MDSTAT);
+        assert_eq!(arrays.len(), 4);
+
+        assert_eq!(arrays[0].level, "raid1");
+        assert_eq!(arrays[0].members.len(), 2);
+        assert!(!arrays[0].is_degraded());
+        assert_eq!(arrays[0].to_string(), "md1 raid1, 2 of 2 devices up");
+
+        assert_eq!(arrays[1].devices_wanted, 3);
+        assert_eq!(arrays[1].devices_up, 2);
+        assert!(arrays[1].is_degraded());
+        assert_eq!(arrays[1].failed_members(), vec!["sdd1"]);
+        let sync = arrays[1].sync.as_ref().unwrap();
+        assert_eq!(sync.action, "recovery");
+        assert_eq!(sync.finish_minutes, Some(127.5));
+        assert_eq!(
+            arrays[1].to_string(),
+            "md0 raid5, 2 of 3 devices up, sdd1 failed, recovery 12.6% done (128 min left)"
+        );
+
+        assert_eq!(arrays[2].level, "raid1");
+        assert!(arrays[2].members[1].spare);
+        assert!(!arrays[2].is_degraded());
+
+        assert!(!arrays[3].active);
+        assert_eq!(arrays[3].to_string(), "md127 inactive");
+    }
+
+    #[test]
+    fn test_parse_smartctl_json() {
+        let ata = smart::parse_smartctl_json("sda", EXAMPLE_SMARTCTL_ATA).unwrap();
+        assert_eq!(ata.model, "WDC WD40EFRX-68N32N0");
+        assert_eq!(ata.passed, Some(true));
+        assert_eq!(ata.temperature, Some(36));
+        assert_eq!(ata.reallocated_sectors, Some(8));
+        assert_eq!(ata.power_on_hours, Some(28_021));
+        assert_eq!(ata.percentage_used, None);
+        // Reallocated sectors mean the disk is on its way out
+        assert!(!ata.is_healthy());
+
+        let nvme = smart::parse_smartctl_json("nvme0n1", EXAMPLE_SMARTCTL_NVME).unwrap();
+        assert_eq!(nvme.percentage_used, Some(3));
+        assert_eq!(nvme.temperature, Some(41));
+        assert_eq!(nvme.reallocated_sectors, None);
+        assert!(nvme.is_healthy());
+        assert_eq!(
+            nvme.to_string(),
+            "nvme0n1 Samsung SSD 970 EVO Plus 1TB: PASSED 41°C, 3% used, 4410 hours on"
+        );
+
+        assert_eq!(
+            smart::parse_smartctl_json("sda", EXAMPLE_SMARTCTL_DENIED),
+            None
+        );
+        assert_eq!(smart::parse_smartctl_json("sda", "not json"), None);
+
+        assert!(smart::is_standby(EXAMPLE_SMARTCTL_STANDBY));
+        assert_eq!(
+            smart::parse_smartctl_json("sda", EXAMPLE_SMARTCTL_STANDBY),
+            None
+        );
+        assert!(!smart::is_standby(EXAMPLE_SMARTCTL_DENIED));
+        assert!(!smart::is_standby(EXAMPLE_SMARTCTL_ATA));
+    }
+
+    #[test]
+    fn test_parse_smartd_state() {
+        let health = smart::parse_smartd_state(
+            "WDC_WD40EFRX_68N32N0-WD_WCC7K1234567.ata",
+            EXAMPLE_SMARTD_STATE,
+        )
+        .unwrap();
+        assert_eq!(health.device, "WDC_WD40EFRX_68N32N0-WD_WCC7K1234567");
+        assert_eq!(health.passed, None);
+        assert_eq!(health.temperature, Some(36));
+        assert_eq!(health.reallocated_sectors, Some(0));
+        assert_eq!(health.power_on_hours, Some(28_021));
+        assert_eq!(
+            smart::parse_smartd_state("x.ata", "temperature-min = 21\n"),
+            None
+        );
+        let odd = EXAMPLE_SMARTD_STATE.replace(".1.raw = 0", ".1.raw = -");
+        let health = smart::parse_smartd_state("x.ata", &odd).unwrap();
+        assert_eq!(health.reallocated_sectors, None);
+        assert_eq!(health.power_on_hours, Some(28_021));
+    }
+
+    #[test]
+    fn test_power_status() {
+        let root = env::temp_dir().join(format!("nmotd-power-{}", std::process::id()));
+        let supplies = [
+            (
+                "AC",
+                "POWER_SUPPLY_NAME=AC\nPOWER_SUPPLY_TYPE=Mains\nPOWER_SUPPLY_ONLINE=0\n",
+            ),
+            (
+                "BAT0",
+                "POWER_SUPPLY_NAME=BAT0
+POWER_SUPPLY_TYPE=Battery
+POWER_SUPPLY_STATUS=Discharging
+POWER_SUPPLY_PRESENT=1
+POWER_SUPPLY_VOLTAGE_NOW=11880000
+POWER_SUPPLY_POWER_NOW=9900000
+POWER_SUPPLY_ENERGY_FULL_DESIGN=57000000
+POWER_SUPPLY_ENERGY_FULL=51300000
+POWER_SUPPLY_ENERGY_NOW=19800000
+POWER_SUPPLY_CAPACITY=38
+",
+            ),
+            (
+                "BAT1",
+                "POWER_SUPPLY_TYPE=Battery
+POWER_SUPPLY_STATUS=Charging
+POWER_SUPPLY_VOLTAGE_NOW=12000000
+POWER_SUPPLY_CURRENT_NOW=1500000
+POWER_SUPPLY_CHARGE_FULL_DESIGN=4000000
+POWER_SUPPLY_CHARGE_FULL=4000000
+POWER_SUPPLY_CHARGE_NOW=1000000
+POWER_SUPPLY_CAPACITY=25
+",
+            ),
+            (
+                "hidpp_battery_0",
+                "POWER_SUPPLY_TYPE=Battery\nPOWER_SUPPLY_SCOPE=Device\nPOWER_SUPPLY_CAPACITY=90\n",
+            ),
+        ];
+        for (name, uevent) in &supplies {
+            std::fs::create_dir_all(root.join(name)).unwrap();
+            std::fs::write(root.join(name).join("uevent"), uevent).unwrap();
+        }
+        let status = power::power_status_in(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(status.ac_online, Some(false));
+        assert_eq!(status.batteries.len(), 2);
+        let bat0 = &status.batteries[0];
+        assert_eq!(bat0.capacity, Some(38));
+        assert_eq!(bat0.power_watts, Some(9.9));
+        assert_eq!(bat0.time_to_empty, Some(Duration::from_secs(7_200)));
+        assert_eq!(bat0.health, Some(90.0));
+        assert_eq!(
+            bat0.to_string(),
+            "38% discharging, 9.9 W, 2:00 left, 90% health"
+        );
+        // Charge based batteries are converted to energy with the voltage
+        let bat1 = &status.batteries[1];
+        assert_eq!(bat1.power_watts, Some(18.0));
+        assert_eq!(bat1.time_to_full, Some(Duration::from_secs(7_200)));
+        assert_eq!(bat1.health, Some(100.0));
+    }
+
+    #[test]
+    fn test_detect_virt() {
+        use virt::VirtProbe;
+
+        assert_eq!(VirtProbe::default().detect(), Virt::BareMetal);
+
+        let kvm = VirtProbe {
+            dmi: vec![
+                "QEMU".to_string(),
+                "Standard PC (Q35 + ICH9, 2009)".to_string(),
+            ],
+            cpu_hypervisor: true,
+            ..VirtProbe::default()
+        };
+        assert_eq!(kvm.detect(), Virt::Vm("QEMU".to_string()));
+        assert_eq!(kvm.detect().to_string(), "QEMU guest");
+
+        let hyperv = VirtProbe {
+            dmi: vec![
+                "Microsoft Corporation".to_string(),
+                "Virtual Machine".to_string(),
+            ],
+            ..VirtProbe::default()
+        };
+        assert_eq!(hyperv.detect(), Virt::Vm("Hyper-V".to_string()));
+
+        // A container inside a VM is reported as the container
+        let docker = VirtProbe {
+            cpu_hypervisor: true,
+            dockerenv: true,
+            ..VirtProbe::default()
+        };
+        assert_eq!(docker.detect().to_string(), "Docker container");
+        assert!(docker.detect().is_container());
+
+        let lxc = VirtProbe {
+            pid1_cgroup: "12:pids:/lxc/web01\n1:name=systemd:/lxc/web01".to_string(),
+            ..VirtProbe::default()
+        };
+        assert_eq!(lxc.detect(), Virt::Container("LXC".to_string()));
+
+        let nspawn = VirtProbe {
+            systemd_container: Some("systemd-nspawn".to_string()),
+            ..VirtProbe::default()
+        };
+        assert_eq!(
+            nspawn.detect(),
+            Virt::Container("systemd-nspawn".to_string())
+        );
+
+        let wsl = VirtProbe {
+            kernel_release: "5.15.90.1-microsoft-standard-WSL2".to_string(),
+            ..VirtProbe::default()
+        };
+        assert_eq!(wsl.detect().to_string(), "WSL2");
+
+        let firecracker = VirtProbe {
+            cpu_hypervisor: true,
+            ..VirtProbe::default()
+        };
+        assert!(!firecracker.detect().is_bare_metal());
+    }
+
+    #[test]
+    fn test_host_info() {
+        let root = env::temp_dir().join(format!("nmotd-dmi-{}", std::process::id()));
+        let dmi_dir = root.join("dmi");
+        std::fs::create_dir_all(&dmi_dir).unwrap();
+        for (file, value) in &[
+            ("sys_vendor", "LENOVO\n"),
+            ("product_name", "20L5CTO1WW\n"),
+            ("product_version", "ThinkPad T480\n"),
+            ("product_serial", "PF1ABCDE\n"),
+            ("board_name", "20L5CTO1WW\n"),
+            ("bios_vendor", "LENOVO\n"),
+            ("bios_version", "N24ET56W (1.31 )\n"),
+            ("bios_date", "02/19/2020\n"),
+            ("chassis_type", "10\n"),
+        ] {
+            std::fs::write(dmi_dir.join(file), value).unwrap();
+        }
+        let pi_model = root.join("model");
+        std::fs::write(&pi_model, "Raspberry Pi 4 Model B Rev 1.4\0").unwrap();
+
+        let thinkpad = dmi::host_info_in(&dmi_dir, &root.join("missing"), false);
+        assert_eq!(
+            thinkpad.description().unwrap(),
+            "LENOVO 20L5CTO1WW ThinkPad T480 (Notebook)"
+        );
+        assert_eq!(
+            thinkpad.firmware().unwrap(),
+            "LENOVO N24ET56W (1.31 ) (02/19/2020)"
+        );
+        assert_eq!(thinkpad.serial, None);
+        let with_serial = dmi::host_info_in(&dmi_dir, &root.join("missing"), true);
+        assert_eq!(with_serial.serial.as_deref(), Some("PF1ABCDE"));
+
+        let pi = dmi::host_info_in(&root.join("no-dmi"), &pi_model, false);
+        assert_eq!(pi.description().unwrap(), "Raspberry Pi 4 Model B Rev 1.4");
+        assert_eq!(pi.firmware(), None);
+
+        std::fs::write(dmi_dir.join("product_name"), "To Be Filled By O.E.M.\n").unwrap();
+        std::fs::write(dmi_dir.join("product_version"), "Default string\n").unwrap();
+        std::fs::write(dmi_dir.join("chassis_type"), "3\n").unwrap();
+        let whitebox = dmi::host_info_in(&dmi_dir, &root.join("missing"), false);
+        assert_eq!(
+            whitebox.description().unwrap(),
+            "LENOVO 20L5CTO1WW (Desktop)"
+        );
+        std::fs::write(dmi_dir.join("sys_vendor"), "System manufacturer\n").unwrap();
+        std::fs::write(dmi_dir.join("board_vendor"), "ASUSTeK COMPUTER INC.\n").unwrap();
+        std::fs::write(dmi_dir.join("board_name"), "PRIME B450M-A\n").unwrap();
+        let whitebox = dmi::host_info_in(&dmi_dir, &root.join("missing"), false);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            whitebox.description().unwrap(),
+            "ASUSTeK COMPUTER INC. PRIME B450M-A (Desktop)"
+        );
+    }
+
+    #[test]
+    fn test_process_sample() {
+        let stat = procfs::process::Stat::from_reader(
+            "1234 (firefox) S 1 1234 1234 0 -1 4194560 500 0 0 0 300 100 0 0 20 0 50 0 1000 \
+             2000000000 25000 18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 0 0 0 0 0 \
+             0 0 0 0 0 0 0"
+                .as_bytes(),
+        )
+        .unwrap();
+        let clock = top::Clock {
+            uptime: Duration::from_secs(100),
+            ticks_per_second: 100,
+        };
+        let cmdline = vec![
+            "/usr/lib/firefox/firefox".to_string(),
+            "-new-tab".to_string(),
+        ];
+        let lifetime = top::process_sample(&stat, &cmdline, "ada".to_string(), None, clock);
+        assert_eq!(lifetime.pid, 1234);
+        assert_eq!(lifetime.command, "/usr/lib/firefox/firefox -new-tab");
+        assert_eq!(lifetime.runtime, Duration::from_secs(90));
+        assert!((lifetime.cpu_percent - 4.444).abs() < 0.01);
+        assert_eq!(lifetime.rss, 25_000 * procfs::page_size().unwrap() as u64);
+
+        let tick = top::process_sample(
+            &stat,
+            &[],
+            "ada".to_string(),
+            Some((300, Duration::from_secs(2))),
+            clock,
+        );
+        assert_eq!(tick.command, "[firefox]");
+        assert!((tick.cpu_percent - 50.0).abs() < 0.01);
+        assert_eq!(top::cpu_percent(100, 100, Duration::from_secs(0)), 0.0);
+
+        let mut processes = vec![
+            top::ProcessSample {
+                pid: 3,
+                cpu_percent: 1.0,
+                rss: 300,
+                ..tick.clone()
+            },
+            top::ProcessSample {
+                pid: 1,
+                cpu_percent: 20.0,
+                rss: 100,
+                ..tick.clone()
+            },
+            top::ProcessSample {
+                pid: 2,
+                cpu_percent: 5.0,
+                rss: 200,
+                ..tick
+            },
+        ];
+        let pids = |processes: &[top::ProcessSample]| {
+            processes
+                .iter()
+                .map(|process| process.pid)
+                .collect::<Vec<_>>()
+        };
+        top::sort_processes(&mut processes, ProcessSort::Cpu);
+        assert_eq!(pids(&processes), vec![1, 2, 3]);
+        top::sort_processes(&mut processes, ProcessSort::Memory);
+        assert_eq!(pids(&processes), vec![3, 2, 1]);
+        top::sort_processes(&mut processes, ProcessSort::Pid);
+        assert_eq!(pids(&processes), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_count_processes() {
+        let stat = |pid: i32, comm: &str, state: char, threads: i64| {
+            procfs::process::Stat::from_reader(
+                format!(
+                    "{} ({}) {} 42 {} {} 0 -1 4194560 500 0 0 0 300 100 0 0 20 0 {} 0 1000 \
+                     2000000000 25000 18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 0 \
+                     0 0 0 0 0 0 0 0 0 0 0",
+                    pid, comm, state, pid, pid, threads
+                )
+                .as_bytes(),
+            )
+            .unwrap()
+        };
+        let stats = [
+            (0, stat(1, "systemd", 'S', 1)),
+            (1000, stat(200, "firefox", 'R', 80)),
+            (1000, stat(201, "defunct", 'Z', 1)),
+            (1000, stat(202, "vim", 'T', 1)),
+            (0, stat(300, "kworker/0:1", 'D', 1)),
+            (33, stat(400, "nginx", 'S', 4)),
+        ];
+        let names: BTreeMap<u32, &str> = BTreeMap::from_iter(vec![(0, "root"), (1000, "ada")]);
+        let counts = process::count_processes(
+            stats.iter().map(|(owner, stat)| (*owner, stat)),
+            1000,
+            |uid| {
+                names
+                    .get(&uid)
+                    .map_or(uid.to_string(), |name| name.to_string())
+            },
+        );
+        assert_eq!((counts.all, counts.root, counts.user), (6, 2, 3));
+        assert_eq!(counts.threads, 88);
+        assert_eq!(
+            counts.by_user,
+            vec![
+                ("ada".to_string(), 3),
+                ("root".to_string(), 2),
+                ("33".to_string(), 1)
+            ]
+        );
+        assert_eq!(counts.zombies.len(), 1);
+        assert_eq!(counts.zombies[0].ppid, 42);
+        assert_eq!(counts.blocked[0].command, "kworker/0:1");
+        assert_eq!(counts.stopped, 1);
+
+        let start = std::time::Instant::now();
+        let mut watch = BlockedWatch::default();
+        watch.update(&counts.blocked, start);
+        assert_eq!(
+            counts.warnings(&watch, start),
+            vec!["1 zombie process not reaped by PID 42"]
+        );
+        let later = start + process::BLOCKED_WARNING;
+        watch.update(&counts.blocked, later);
+        assert_eq!(
+            counts.warnings(&watch, later)[1],
+            "kworker/0:1 (300) in uninterruptible sleep for 2m"
+        );
+        watch.update(&[], later);
+        assert_eq!(
+            watch.blocked_for(&counts.blocked[0], later),
+            Duration::default()
+        );
+    }
+
+    #[test]
+    fn test_users() {
+        let root = users::user(0).unwrap();
+        assert_eq!(root.name, "root");
+        assert_eq!(root.gid, 0);
+        assert_eq!(root.home, PathBuf::from("/root"));
+        assert_eq!(users::group_name(0).as_deref(), Some("root"));
+        assert_eq!(users::user(4_000_000_000), None);
+        assert_eq!(users::group_name(4_000_000_000), None);
+
+        let mut cache = HashMap::new();
+        let failed = || Err(std::io::Error::from_raw_os_error(libc::EIO));
+        assert_eq!(
+            users::cached(&mut cache, 1000, |_| failed()),
+            None::<String>
+        );
+        let found = |_| Ok(Some("alice".to_string()));
+        assert_eq!(
+            users::cached(&mut cache, 1000, found).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(users::cached(&mut cache, 1001, |_| Ok(None)), None);
+        assert_eq!(users::cached(&mut cache, 1001, found), None);
+
+        assert_eq!(users::full_name("Ada Lovelace,,,"), Some("Ada Lovelace"));
+        assert_eq!(users::full_name(" ,Room 42"), None);
+        assert_eq!(users::full_name(""), None);
+    }
+
+    #[test]
+    fn test_greeting() {
+        use greeting::{render, time_of_day, Field};
+        let facts = GreetingFacts {
+            time_of_day: time_of_day(9).to_string(),
+            name: "Ada Lovelace".to_string(),
+            user: "ada".to_string(),
+            host: "engine".to_string(),
+            os: "Pop!_OS 20.04 LTS".to_string(),
+            last_login: None,
+        };
+        assert_eq!(
+            render(
+                "Good {time_of_day}, {name}! {unknown} {os} on {host} {",
+                &facts
+            ),
+            vec![
+                (None, "Good ".to_string()),
+                (Some(Field::TimeOfDay), "morning".to_string()),
+                (None, ", ".to_string()),
+                (Some(Field::Name), "Ada Lovelace".to_string()),
+                (None, "! {unknown} ".to_string()),
+                (Some(Field::Os), "Pop!_OS 20.04 LTS".to_string()),
+                (None, " on ".to_string()),
+                (Some(Field::Host), "engine".to_string()),
+                (None, " {".to_string()),
+            ]
+        );
+        assert_eq!(
+            render("{last_login}", &facts),
+            vec![(Some(Field::LastLogin), "never".to_string())]
+        );
+        assert_eq!(time_of_day(13), "afternoon");
+        assert_eq!(time_of_day(2), "evening");
+    }
+
+    #[test]
+    fn test_os_release_fallbacks() {
+        let root = env::temp_dir().join(format!("nmotd-os-release-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("etc")).unwrap();
+        std::fs::create_dir_all(root.join("usr/lib")).unwrap();
+        let write = |path: &str, content: &str| std::fs::write(root.join(path), content).unwrap();
+
+        write("etc/GoboLinuxVersion", "017\n");
+        let gobo = OsRelease::new_in(&root).unwrap();
+        assert_eq!(gobo.id, "gobolinux");
+        assert_eq!(gobo.pretty_name, "GoboLinux 017");
+
+        write("etc/redhat-release", "CentOS release 6.10 (Final)\n");
+        write("etc/centos-release", "CentOS release 6.10 (Final)\n");
+        let centos = OsRelease::new_in(&root).unwrap();
+        assert_eq!(centos.id, "centos");
+        assert_eq!(centos.version_id, "6.10");
+        assert_eq!(centos.description(), "CentOS release 6.10 (Final)");
+
+        write(
+            "etc/lsb-release",
+            "DISTRIB_ID=Ubuntu\nDISTRIB_RELEASE=12.04\nDISTRIB_CODENAME=precise\n\
+             DISTRIB_DESCRIPTION=\"Ubuntu 12.04.5 LTS\"\n",
+        );
+        let ubuntu = OsRelease::new_in(&root).unwrap();
+        assert_eq!(
+            (ubuntu.id.as_str(), ubuntu.version_codename.as_str()),
+            ("ubuntu", "precise")
+        );
+        assert_eq!(ubuntu.pretty_name, "Ubuntu 12.04.5 LTS");
+
+        write(
+            "usr/lib/os-release",
+            "NAME=Fedora\nID=fedora\nPRETTY_NAME=\"Fedora 33\"\nVARIANT=\"Workstation Edition\"\n\
+             VARIANT_ID=workstation\nBUILD_ID=20201020\nANSI_COLOR=\"0;38;2;60;110;180\"\n\
+             LOGO=fedora-logo-icon\n",
+        );
+        let fedora = OsRelease::new_in(&root).unwrap();
+        assert_eq!(fedora.variant_id, "workstation");
+        assert_eq!(fedora.ansi_color, "0;38;2;60;110;180");
+        assert_eq!(fedora.logo, "fedora-logo-icon");
+        assert!(fedora.extra.is_empty());
+        assert_eq!(
+            fedora.description(),
+            "Fedora 33 (Workstation Edition, build 20201020)"
+        );
+
+        write("etc/os-release", "ID=arch\n");
+        assert_eq!(OsRelease::new_in(&root).unwrap().description(), "Linux");
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(OsRelease::new_in(&root).is_err());
+    }
+
+    #[test]
+    fn test_ansi_color() {
+        use super::os_release::parse_ansi_color;
+        assert_eq!(
+            parse_ansi_color("0;38;2;60;110;180"),
+            Some(AnsiColor::Rgb(60, 110, 180))
+        );
+        assert_eq!(parse_ansi_color("1;34"), Some(AnsiColor::Indexed(4)));
+        assert_eq!(parse_ansi_color("38;5;208"), Some(AnsiColor::Indexed(208)));
+        assert_eq!(parse_ansi_color("1;92"), Some(AnsiColor::Indexed(10)));
+        assert_eq!(parse_ansi_color("1"), None);
+        assert_eq!(parse_ansi_color(""), None);
+        assert_eq!(parse_ansi_color("38;2;1"), None);
+    }
 }
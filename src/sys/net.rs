@@ -0,0 +1,136 @@
+use procfs::net::{TcpNetEntry, TcpState, UdpNetEntry, UdpState};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+/// A socket accepting connections (TCP) or datagrams (UDP) on this host.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListeningPort {
+    pub protocol: Protocol,
+    pub address: SocketAddr,
+    /// Name of the owning process, if we were allowed to look into its `/proc/<pid>/fd`.
+    pub process: Option<String>,
+}
+
+/// Established TCP connections grouped by the remote end.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteConnections {
+    pub address: IpAddr,
+    pub count: usize,
+}
+
+/// Maps socket inodes to the name of the process holding them open.
+///
+/// Only processes we are allowed to inspect are considered, so unless we run as root this
+/// mostly covers the current user's processes.
+pub fn socket_owners() -> HashMap<u32, String> {
+    let mut owners = HashMap::new();
+    let process_list = match procfs::process::all_processes() {
+        Ok(process_list) => process_list,
+        Err(_) => return owners,
+    };
+    for process in process_list {
+        if let Ok(fds) = process.fd() {
+            for fd in fds {
+                if let procfs::process::FDTarget::Socket(inode) = fd.target {
+                    owners
+                        .entry(inode)
+                        .or_insert_with(|| process.stat.comm.clone());
+                }
+            }
+        }
+    }
+    owners
+}
+
+pub fn listening_ports() -> Vec<ListeningPort> {
+    let tcp = tcp_table();
+    let udp = udp_table();
+    listening_from(&tcp, &udp, &socket_owners())
+}
+
+pub fn connections_by_remote() -> Vec<RemoteConnections> {
+    connections_from(&tcp_table())
+}
+
+fn tcp_table() -> Vec<TcpNetEntry> {
+    let mut table = procfs::net::tcp().unwrap_or_default();
+    table.extend(procfs::net::tcp6().unwrap_or_default());
+    table
+}
+
+fn udp_table() -> Vec<UdpNetEntry> {
+    let mut table = procfs::net::udp().unwrap_or_default();
+    table.extend(procfs::net::udp6().unwrap_or_default());
+    table
+}
+
+pub fn listening_from(
+    tcp: &[TcpNetEntry],
+    udp: &[UdpNetEntry],
+    owners: &HashMap<u32, String>,
+) -> Vec<ListeningPort> {
+    let tcp = tcp
+        .iter()
+        .filter(|entry| entry.state == TcpState::Listen)
+        .map(|entry| (Protocol::Tcp, entry.local_address, entry.inode));
+    // Unconnected UDP sockets show up in the `Close` state with an unspecified remote address.
+    let udp = udp
+        .iter()
+        .filter(|entry| {
+            entry.state == UdpState::Close && entry.remote_address.ip().is_unspecified()
+        })
+        .map(|entry| (Protocol::Udp, entry.local_address, entry.inode));
+
+    let mut ports: Vec<ListeningPort> = tcp
+        .chain(udp)
+        .map(|(protocol, address, inode)| ListeningPort {
+            protocol,
+            address,
+            process: owners.get(&inode).cloned(),
+        })
+        .collect();
+    ports.sort_by(|a, b| {
+        (a.address.port(), a.protocol, a.address.ip()).cmp(&(
+            b.address.port(),
+            b.protocol,
+            b.address.ip(),
+        ))
+    });
+    ports.dedup();
+    ports
+}
+
+/// Counts established TCP connections per remote address, busiest first.
+pub fn connections_from(tcp: &[TcpNetEntry]) -> Vec<RemoteConnections> {
+    let mut counts: HashMap<IpAddr, usize> = HashMap::new();
+    for entry in tcp
+        .iter()
+        .filter(|entry| entry.state == TcpState::Established)
+    {
+        *counts.entry(entry.remote_address.ip()).or_insert(0) += 1;
+    }
+    let mut connections: Vec<RemoteConnections> = counts
+        .into_iter()
+        .map(|(address, count)| RemoteConnections { address, count })
+        .collect();
+    connections.sort_by(|a, b| b.count.cmp(&a.count).then(a.address.cmp(&b.address)));
+    connections
+}
@@ -2,7 +2,7 @@
 
 use std::{
     collections::BTreeMap,
-    fs::File,
+    fs::{self, File},
     io::{self, BufRead, BufReader},
     iter::FromIterator,
     path::Path,
@@ -79,15 +79,89 @@ pub struct OsRelease {
     ///
     /// **IE:** `18.04`
     pub version: String,
+    /// The edition of this release, if the distro ships several.
+    ///
+    /// **IE:** `Workstation Edition`
+    pub variant: String,
+    /// Lowercase identifier of the variant.
+    ///
+    /// **IE:** `workstation`
+    pub variant_id: String,
+    /// Identifier of the system image the OS was installed or updated from.
+    ///
+    /// **IE:** `2013-03-20.3`
+    pub build_id: String,
+    /// SGR parameters the distro suggests for showing its name.
+    ///
+    /// **IE:** `0;38;2;60;110;180`
+    pub ansi_color: String,
+    /// Name of the distro's logo icon, as in the freedesktop icon theme spec.
+    ///
+    /// **IE:** `fedora-logo-icon`
+    pub logo: String,
     /// Additional keys not covered by the API.
     pub extra: BTreeMap<String, String>,
 }
 
 impl OsRelease {
-    /// Attempt to parse the contents of `/etc/os-release`.
+    /// Attempt to parse the contents of `/etc/os-release`, falling back to
+    /// `/usr/lib/os-release`, `/etc/lsb-release` and the legacy `/etc/*-release` files.
     pub fn new() -> io::Result<OsRelease> {
-        let file = BufReader::new(open("/etc/os-release")?);
-        Ok(OsRelease::from_iter(file.lines().flatten()))
+        OsRelease::new_in(Path::new("/"))
+    }
+
+    /// Like `new`, with the files looked up under `root` instead of `/`.
+    pub fn new_in(root: &Path) -> io::Result<OsRelease> {
+        for path in &OS_RELEASE_PATHS {
+            if let Ok(os_release) = OsRelease::new_from(root.join(path)) {
+                return Ok(os_release);
+            }
+        }
+        if let Some(os_release) = fs::read_to_string(root.join("etc/lsb-release"))
+            .ok()
+            .and_then(|content| parse_lsb_release(&content))
+        {
+            return Ok(os_release);
+        }
+        legacy_release(root).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no os-release, lsb-release or legacy release file under {:?}",
+                    root
+                ),
+            )
+        })
+    }
+
+    /// The pretty name (`Linux` by default, as the spec says), plus the variant and build
+    /// ID when the pretty name doesn't already mention them.
+    ///
+    /// **IE:** `Fedora 33 (Workstation Edition)` or `Endless OS 3.9 (build 190415)`
+    pub fn description(&self) -> String {
+        let mut description = if !self.pretty_name.is_empty() {
+            self.pretty_name.clone()
+        } else if !self.name.is_empty() {
+            self.name.clone()
+        } else {
+            "Linux".to_string()
+        };
+        let mut details = Vec::new();
+        if !self.variant.is_empty() && !description.contains(&self.variant) {
+            details.push(self.variant.clone());
+        }
+        if !self.build_id.is_empty() && !description.contains(&self.build_id) {
+            details.push(format!("build {}", self.build_id));
+        }
+        if !details.is_empty() {
+            description.push_str(&format!(" ({})", details.join(", ")));
+        }
+        description
+    }
+
+    /// The colour `ANSI_COLOR` asks for, if it sets one.
+    pub fn color(&self) -> Option<AnsiColor> {
+        parse_ansi_color(&self.ansi_color)
     }
 
     /// Attempt to parse any `/etc/os-release`-like file.
@@ -114,7 +188,12 @@ impl FromIterator<String> for OsRelease {
                 "SUPPORT_URL=" => os_release.support_url,
                 "BUG_REPORT_URL=" => os_release.bug_report_url,
                 "PRIVACY_POLICY_URL=" => os_release.privacy_policy_url,
-                "VERSION_CODENAME=" => os_release.version_codename
+                "VERSION_CODENAME=" => os_release.version_codename,
+                "VARIANT=" => os_release.variant,
+                "VARIANT_ID=" => os_release.variant_id,
+                "BUILD_ID=" => os_release.build_id,
+                "ANSI_COLOR=" => os_release.ansi_color,
+                "LOGO=" => os_release.logo
             });
 
             if let Some(pos) = line.find('=') {
@@ -142,25 +221,160 @@ fn open<P: AsRef<Path>>(path: P) -> io::Result<File> {
 // Note Everything Above This is came from
 // https://github.com/pop-os/os-release/blob/master/src/lib.rs
 // Note new_from can parse any os-release type file
-pub fn distro() -> String {
-    if Path::new("/etc/os-release").is_file() {
-        let release = OsRelease::new().expect("Error Will Robinson");
-        release.pretty_name
-    } else if Path::new("/etc/redstar-release").is_file() {
-        String::from("Red Star OS")
-    } else if Path::new("/etc/siduction-version").is_file() {
-        String::from("Siductiond")
-    } else if Path::new("/etc/GoboLinuxVersion").is_file() {
-        String::from("GoboLinux")
-    } else if Path::new("/etc/pcbsd-lang").is_file() {
-        String::from("PCBSD")
-    } else if Path::new("/etc/trueos-lang").is_file() {
-        String::from("TrueOS")
-    } else if Path::new("/bedrock/etc/bedrock-release").is_file() {
-        String::from("Bedrock Linux")
-    } else if Path::new("/etc/pacbsd-release").is_file() {
-        String::from("PacBSD")
+
+/// Where os-release(5) says to look, in order, relative to the root.
+const OS_RELEASE_PATHS: [&str; 2] = ["etc/os-release", "usr/lib/os-release"];
+
+/// Distros that don't ship an os-release, recognised by a file of their own. The file's
+/// first line, if any, is taken as the version.
+const VERSION_FILES: [(&str, &str, &str); 3] = [
+    ("bedrock/etc/bedrock-release", "bedrock", "Bedrock Linux"),
+    ("etc/siduction-version", "siduction", "siduction"),
+    ("etc/GoboLinuxVersion", "gobolinux", "GoboLinux"),
+];
+
+/// A foreground colour from an SGR sequence, for front ends to map to their own type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnsiColor {
+    /// One of the 256 palette colours, the first 16 being the basic and bright ones.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// The foreground colour of SGR parameters like `1;34`, `38;5;208` or `0;38;2;60;110;180`.
+/// Attributes are skipped, and the last colour wins as it would on a terminal.
+pub fn parse_ansi_color(sgr: &str) -> Option<AnsiColor> {
+    let params: Vec<u8> = sgr
+        .split(';')
+        .map(|param| param.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let mut color = None;
+    let mut i = 0;
+    while i < params.len() {
+        match params[i] {
+            code @ 30..=37 => color = Some(AnsiColor::Indexed(code - 30)),
+            code @ 90..=97 => color = Some(AnsiColor::Indexed(code - 90 + 8)),
+            38 => match params.get(i + 1) {
+                Some(5) => {
+                    color = params.get(i + 2).copied().map(AnsiColor::Indexed);
+                    i += 2;
+                }
+                Some(2) => {
+                    if let [r, g, b] = params.get(i + 2..i + 5)? {
+                        color = Some(AnsiColor::Rgb(*r, *g, *b));
+                    }
+                    i += 4;
+                }
+                _ => return color,
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    color
+}
+
+/// Parses `/etc/lsb-release`, which needs at least a `DISTRIB_ID`.
+pub fn parse_lsb_release(content: &str) -> Option<OsRelease> {
+    let mut os_release = OsRelease::default();
+    let mut description = String::new();
+    for line in content.lines() {
+        let line = line.trim();
+        let (key, value) = match line.find('=') {
+            Some(pos) => (&line[..pos], parse_line(line, pos + 1)),
+            None => continue,
+        };
+        match key {
+            "DISTRIB_ID" => os_release.name = value.to_string(),
+            "DISTRIB_RELEASE" => os_release.version_id = value.to_string(),
+            "DISTRIB_CODENAME" => os_release.version_codename = value.to_string(),
+            "DISTRIB_DESCRIPTION" => description = value.to_string(),
+            _ => {}
+        }
+    }
+    if os_release.name.is_empty() {
+        return None;
+    }
+    os_release.id = os_release.name.to_lowercase().replace(' ', "");
+    os_release.version = os_release.version_id.clone();
+    os_release.pretty_name = if description.is_empty() {
+        format!("{} {}", os_release.name, os_release.version_id)
+            .trim()
+            .to_string()
+    } else {
+        description
+    };
+    Some(os_release)
+}
+
+/// Builds a release from a legacy one-line file like `/etc/redhat-release`, named after
+/// the file with its contents as the pretty name.
+pub fn parse_legacy_release(id: &str, name: &str, content: &str) -> OsRelease {
+    let first_line = content.lines().next().unwrap_or("").trim();
+    let version = first_line
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or("");
+    let pretty_name = if first_line.is_empty() {
+        name.to_string()
+    } else if first_line.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("{} {}", name, first_line)
     } else {
-        "Unknown".to_string()
+        first_line.to_string()
+    };
+    OsRelease {
+        id: id.to_string(),
+        name: name.to_string(),
+        pretty_name,
+        version: version.to_string(),
+        version_id: version.to_string(),
+        ..OsRelease::default()
     }
 }
+
+/// Looks through `/etc/*-release` (the distro specific ones before catch-alls like
+/// `redhat-release` and `system-release`), then the files of distros without any.
+fn legacy_release(root: &Path) -> Option<OsRelease> {
+    let mut files: Vec<String> = fs::read_dir(root.join("etc"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .filter(|name| name.ends_with("-release"))
+                .filter(|name| name != "os-release" && name != "lsb-release")
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by_key(|name| {
+        (
+            name == "redhat-release" || name == "system-release",
+            name.clone(),
+        )
+    });
+    for file in files {
+        let id = file.trim_end_matches("-release");
+        if let Ok(content) = fs::read_to_string(root.join("etc").join(&file)) {
+            let name = capitalize(id);
+            return Some(parse_legacy_release(id, &name, &content));
+        }
+    }
+    VERSION_FILES.iter().find_map(|(path, id, name)| {
+        let content = fs::read_to_string(root.join(path)).ok()?;
+        Some(parse_legacy_release(id, name, &content))
+    })
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The distro's description, or "Unknown" when there's nothing to go by.
+pub fn distro() -> String {
+    OsRelease::new()
+        .map(|release| release.description())
+        .unwrap_or_else(|_| "Unknown".to_string())
+}
@@ -0,0 +1,142 @@
+// Batteries and AC adapters, from `/sys/class/power_supply`. Each supply's `uevent` file
+// carries all of its properties as `POWER_SUPPLY_*=value` lines.
+
+use super::format_idle;
+use std::{collections::HashMap, fmt, fs, path::Path, time::Duration};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Battery {
+    pub name: String,
+    /// Percent of the current full charge.
+    pub capacity: Option<u64>,
+    /// `Charging`, `Discharging`, `Full` or `Not charging`.
+    pub status: String,
+    /// Rate the battery is being charged or drained at.
+    pub power_watts: Option<f64>,
+    pub time_to_empty: Option<Duration>,
+    pub time_to_full: Option<Duration>,
+    /// Full charge relative to the design capacity, in percent.
+    pub health: Option<f64>,
+}
+
+impl fmt::Display for Battery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(capacity) = self.capacity {
+            write!(f, "{}% ", capacity)?;
+        }
+        write!(f, "{}", self.status.to_lowercase())?;
+        if let Some(watts) = self.power_watts {
+            write!(f, ", {:.1} W", watts)?;
+        }
+        if let Some(time) = self.time_to_empty {
+            write!(f, ", {} left", format_idle(time))?;
+        } else if let Some(time) = self.time_to_full {
+            write!(f, ", {} to full", format_idle(time))?;
+        }
+        if let Some(health) = self.health {
+            write!(f, ", {:.0}% health", health)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PowerStatus {
+    /// `None` when there's no mains adapter, e.g. on desktops and servers.
+    pub ac_online: Option<bool>,
+    pub batteries: Vec<Battery>,
+}
+
+pub fn power_status() -> PowerStatus {
+    power_status_in(Path::new("/sys/class/power_supply"))
+}
+
+/// Reads every supply below `root`, which is `/sys/class/power_supply` outside of tests.
+pub fn power_status_in(root: &Path) -> PowerStatus {
+    let mut status = PowerStatus::default();
+    let mut names: Vec<String> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    for name in names {
+        let uevent = match fs::read_to_string(root.join(&name).join("uevent")) {
+            Ok(uevent) => parse_uevent(&uevent),
+            Err(_) => continue,
+        };
+        match uevent.get("TYPE").map(String::as_str) {
+            Some("Mains") | Some("USB") => {
+                let online = uevent.get("ONLINE").map(String::as_str) == Some("1");
+                status.ac_online = Some(status.ac_online.unwrap_or(false) || online);
+            }
+            // Wireless mice and keyboards report their batteries with a `Device` scope
+            Some("Battery") if uevent.get("SCOPE").map(String::as_str) != Some("Device") => {
+                status.batteries.push(battery_from(&name, &uevent));
+            }
+            _ => {}
+        }
+    }
+    status
+}
+
+/// Maps `POWER_SUPPLY_CAPACITY=87` to `CAPACITY` => `87`.
+pub fn parse_uevent(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((
+                key.strip_prefix("POWER_SUPPLY_").unwrap_or(key).to_string(),
+                value.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Batteries report either energy (µWh) and power (µW), or charge (µAh) and current (µA)
+/// along with the voltage (µV).
+pub fn battery_from(name: &str, uevent: &HashMap<String, String>) -> Battery {
+    let number = |key: &str| uevent.get(key)?.trim().parse::<f64>().ok();
+    let voltage = number("VOLTAGE_NOW").map(|microvolts| microvolts / 1_000_000.0);
+    let energy = |energy_key: &str, charge_key: &str| {
+        number(energy_key).or_else(|| Some(number(charge_key)? * voltage?))
+    };
+    let now = energy("ENERGY_NOW", "CHARGE_NOW");
+    let full = energy("ENERGY_FULL", "CHARGE_FULL");
+    let full_design = energy("ENERGY_FULL_DESIGN", "CHARGE_FULL_DESIGN");
+    // µW, some drivers report a negative rate while discharging
+    let power = number("POWER_NOW")
+        .or_else(|| Some(number("CURRENT_NOW")? * voltage?))
+        .map(f64::abs)
+        .filter(|power| *power > 0.0);
+    let status = uevent.get("STATUS").cloned().unwrap_or_default();
+    let hours_to_duration = |hours: f64| Duration::from_secs((hours * 3_600.0) as u64);
+    let time_to_empty = match (status.as_str(), now, power) {
+        ("Discharging", Some(now), Some(power)) => Some(hours_to_duration(now / power)),
+        _ => None,
+    };
+    let time_to_full = match (status.as_str(), now, full, power) {
+        ("Charging", Some(now), Some(full), Some(power)) => {
+            Some(hours_to_duration((full - now).max(0.0) / power))
+        }
+        _ => None,
+    };
+    Battery {
+        name: name.to_string(),
+        capacity: uevent
+            .get("CAPACITY")
+            .and_then(|capacity| capacity.parse().ok()),
+        status,
+        power_watts: power.map(|power| power / 1_000_000.0),
+        time_to_empty,
+        time_to_full,
+        health: match (full, full_design) {
+            (Some(full), Some(design)) if design > 0.0 => Some(full / design * 100.0),
+            _ => None,
+        },
+    }
+}
@@ -0,0 +1,19 @@
+use procfs::{CpuPressure, IoPressure, MemoryPressure};
+
+/// Share of the last 10 seconds in which at least one task was stalled waiting on a
+/// resource, from `/proc/pressure/*`. `None` on kernels without PSI (before 4.20, or
+/// booted with `psi=0`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pressure {
+    pub cpu: Option<f32>,
+    pub memory: Option<f32>,
+    pub io: Option<f32>,
+}
+
+pub fn pressure() -> Pressure {
+    Pressure {
+        cpu: CpuPressure::new().ok().map(|p| p.some.avg10),
+        memory: MemoryPressure::new().ok().map(|p| p.some.avg10),
+        io: IoPressure::new().ok().map(|p| p.some.avg10),
+    }
+}
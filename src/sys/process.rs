@@ -1,96 +1,151 @@
-use std::{ffi::c_void, mem};
+use super::{
+    format_idle,
+    users::{euid, user_name},
+};
+use procfs::process::Stat;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-#[repr(C)]
-struct PassWd {
-    pw_name: *const c_void,
-    pw_passwd: *const c_void,
-    pw_uid: u32,
-    pw_gid: u32,
-    pw_gecos: *const c_void,
-    pw_dir: *const c_void,
-    pw_shell: *const c_void,
-}
+/// How long a process may sit in uninterruptible sleep before it's worth a warning.
+/// Matches the kernel's default `hung_task_timeout_secs`.
+pub const BLOCKED_WARNING: Duration = Duration::from_secs(120);
 
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ProcessByUser {
     pub root: usize,
     pub user: usize,
     pub all: usize,
+    /// Process count of every user, most processes first.
+    pub by_user: Vec<(String, usize)>,
+    pub threads: usize,
+    pub zombies: Vec<ProcessEntry>,
+    /// Processes in uninterruptible sleep (`D`), usually waiting on I/O.
+    pub blocked: Vec<ProcessEntry>,
+    /// Processes stopped by a signal or a debugger.
+    pub stopped: usize,
 }
 
-pub fn process_by_user() -> ProcessByUser {
-    let process_list = procfs::process::all_processes().expect("Error getting list of processes");
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessEntry {
+    pub pid: i32,
+    pub ppid: i32,
+    pub command: String,
+    /// Clock ticks after boot, which together with the PID tells a reused PID apart.
+    pub starttime: u64,
+}
 
-    let mut root: usize = 0;
-    let mut user: usize = 0;
-    for process in &process_list {
-        if process.owner == 0 {
-            root += 1
-        } else if process.owner == unsafe { geteuid() } {
-            user += 1
+impl ProcessEntry {
+    fn from_stat(stat: &Stat) -> ProcessEntry {
+        ProcessEntry {
+            pid: stat.pid,
+            ppid: stat.ppid,
+            command: stat.comm.clone(),
+            starttime: stat.starttime,
         }
     }
+}
 
-    ProcessByUser {
-        root,
-        user,
-        all: process_list.len(),
+impl ProcessByUser {
+    /// Zombies waiting to be reaped, and processes stuck in uninterruptible sleep for at
+    /// least `BLOCKED_WARNING` according to `watch`.
+    pub fn warnings(&self, watch: &BlockedWatch, now: Instant) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.zombies.is_empty() {
+            let mut parents: Vec<i32> = self.zombies.iter().map(|zombie| zombie.ppid).collect();
+            parents.sort_unstable();
+            parents.dedup();
+            let parents: Vec<String> = parents.iter().map(i32::to_string).collect();
+            warnings.push(format!(
+                "{} zombie process{} not reaped by PID {}",
+                self.zombies.len(),
+                if self.zombies.len() == 1 { "" } else { "es" },
+                parents.join(", ")
+            ));
+        }
+        for process in &self.blocked {
+            let blocked_for = watch.blocked_for(process, now);
+            if blocked_for >= BLOCKED_WARNING {
+                warnings.push(format!(
+                    "{} ({}) in uninterruptible sleep for {}",
+                    process.command,
+                    process.pid,
+                    format_idle(blocked_for)
+                ));
+            }
+        }
+        warnings
     }
 }
-extern "system" {
-    fn getpwuid_r(
-        uid: u32,
-        pwd: *mut PassWd,
-        buf: *mut c_void,
-        buflen: usize,
-        result: *mut *mut PassWd,
-    ) -> i32;
-    fn geteuid() -> u32;
-    fn strlen(cs: *const c_void) -> usize;
-}
-
-#[inline(always)]
-fn getpwuid() -> (String, String) {
-    const BUF_SIZE: usize = 16_384; // size from the man page
-    let mut buffer = mem::MaybeUninit::<[u8; BUF_SIZE]>::uninit();
-    let mut passwd = mem::MaybeUninit::<PassWd>::uninit();
-    let mut _passwd = mem::MaybeUninit::<*mut PassWd>::uninit();
-
-    // Get PassWd `struct`.
-    let passwd = unsafe {
-        getpwuid_r(
-            geteuid(),
-            passwd.as_mut_ptr(),
-            buffer.as_mut_ptr() as *mut c_void,
-            BUF_SIZE,
-            _passwd.as_mut_ptr(),
-        );
-
-        passwd.assume_init()
-    };
 
-    // Extract names.
-    let a = string_from_cstring(passwd.pw_name);
-    let b = string_from_cstring(passwd.pw_gecos);
-
-    (a, b)
+/// `/proc` only has a process' current state, so this remembers when each one was first
+/// seen in uninterruptible sleep.
+#[derive(Clone, Debug, Default)]
+pub struct BlockedWatch {
+    since: HashMap<(i32, u64), Instant>,
 }
 
-fn string_from_cstring(string: *const c_void) -> String {
-    if string.is_null() {
-        return "".to_string();
+impl BlockedWatch {
+    pub fn update(&mut self, blocked: &[ProcessEntry], now: Instant) {
+        let mut since = HashMap::new();
+        for process in blocked {
+            let key = (process.pid, process.starttime);
+            since.insert(key, *self.since.get(&key).unwrap_or(&now));
+        }
+        self.since = since;
     }
 
-    // Get a byte slice of the c string.
-    let slice = unsafe {
-        let length = strlen(string);
-        std::slice::from_raw_parts(string as *const u8, length)
-    };
+    pub fn blocked_for(&self, process: &ProcessEntry, now: Instant) -> Duration {
+        match self.since.get(&(process.pid, process.starttime)) {
+            Some(since) => now.saturating_duration_since(*since),
+            None => Duration::default(),
+        }
+    }
+}
 
-    // Turn byte slice into Rust String.
-    String::from_utf8_lossy(slice).to_string()
+/// Breakdown of `processes`, given as owner and stat, with `name` resolving owners.
+pub fn count_processes<'a, I, F>(processes: I, euid: u32, mut name: F) -> ProcessByUser
+where
+    I: IntoIterator<Item = (u32, &'a Stat)>,
+    F: FnMut(u32) -> String,
+{
+    let mut counts = ProcessByUser::default();
+    let mut by_uid: HashMap<u32, usize> = HashMap::new();
+    for (owner, stat) in processes {
+        counts.all += 1;
+        if owner == 0 {
+            counts.root += 1
+        } else if owner == euid {
+            counts.user += 1
+        }
+        *by_uid.entry(owner).or_default() += 1;
+        counts.threads += stat.num_threads.max(1) as usize;
+        match stat.state {
+            'Z' => counts.zombies.push(ProcessEntry::from_stat(stat)),
+            'D' => counts.blocked.push(ProcessEntry::from_stat(stat)),
+            'T' | 't' => counts.stopped += 1,
+            _ => {}
+        }
+    }
+    counts.by_user = by_uid
+        .into_iter()
+        .map(|(uid, count)| (name(uid), count))
+        .collect();
+    counts
+        .by_user
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
 }
 
-pub fn username() -> String {
-    let pwent = getpwuid();
-    pwent.0
+pub fn process_by_user() -> ProcessByUser {
+    let process_list = procfs::process::all_processes().expect("Error getting list of processes");
+
+    count_processes(
+        process_list
+            .iter()
+            .map(|process| (process.owner, &process.stat)),
+        euid(),
+        |uid| user_name(uid).unwrap_or_else(|| uid.to_string()),
+    )
 }
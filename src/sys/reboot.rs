@@ -0,0 +1,152 @@
+use super::get_kernel;
+use std::{fmt, fs, path::Path};
+
+/// Why the host should be rebooted (or at least have some processes restarted).
+#[derive(Clone, Debug, PartialEq)]
+pub enum RebootReason {
+    /// Debian-family packages touched `/var/run/reboot-required`, listing themselves in
+    /// `/var/run/reboot-required.pkgs`.
+    Flagged { packages: Vec<String> },
+    /// The running kernel's modules are gone, usually because a kernel upgrade removed them.
+    KernelMismatch {
+        running: String,
+        installed: Vec<String>,
+    },
+    /// Processes still mapping shared libraries that were replaced on disk.
+    DeletedLibraries { processes: Vec<StaleProcess> },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StaleProcess {
+    pub pid: i32,
+    pub name: String,
+    pub libraries: Vec<String>,
+}
+
+impl fmt::Display for RebootReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebootReason::Flagged { packages } if packages.is_empty() => {
+                write!(f, "Reboot required by package updates")
+            }
+            RebootReason::Flagged { packages } => {
+                write!(f, "Reboot required by {}", packages.join(", "))
+            }
+            RebootReason::KernelMismatch { running, .. } => {
+                write!(f, "Running kernel {} is no longer installed", running)
+            }
+            RebootReason::DeletedLibraries { processes } => {
+                let names: Vec<String> = processes
+                    .iter()
+                    .map(|process| format!("{}[{}]", process.name, process.pid))
+                    .collect();
+                write!(f, "Using deleted libraries: {}", names.join(", "))
+            }
+        }
+    }
+}
+
+pub fn reboot_required() -> Vec<RebootReason> {
+    let mut reasons = Vec::new();
+    if Path::new("/var/run/reboot-required").exists() {
+        let packages = fs::read_to_string("/var/run/reboot-required.pkgs").unwrap_or_default();
+        reasons.push(RebootReason::Flagged {
+            packages: parse_reboot_required_pkgs(&packages),
+        });
+    }
+    if let Some(reason) = kernel_mismatch() {
+        reasons.push(reason);
+    }
+    let processes = stale_processes();
+    if !processes.is_empty() {
+        reasons.push(RebootReason::DeletedLibraries { processes });
+    }
+    reasons
+}
+
+/// One package per line, possibly repeated when several triggers fired.
+pub fn parse_reboot_required_pkgs(content: &str) -> Vec<String> {
+    let mut packages: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+fn kernel_mismatch() -> Option<RebootReason> {
+    let running = get_kernel().ok()?;
+    let mut installed = Vec::new();
+    let mut found_modules = false;
+    for dir in &["/usr/lib/modules", "/lib/modules"] {
+        if let Ok(entries) = fs::read_dir(dir) {
+            found_modules = true;
+            installed.extend(
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string()),
+            );
+        }
+    }
+    installed.sort();
+    installed.dedup();
+    // Containers and some minimal images ship no modules at all, which isn't a mismatch.
+    if !found_modules || installed.is_empty() || installed.contains(&running) {
+        None
+    } else {
+        Some(RebootReason::KernelMismatch { running, installed })
+    }
+}
+
+fn stale_processes() -> Vec<StaleProcess> {
+    let process_list = match procfs::process::all_processes() {
+        Ok(process_list) => process_list,
+        Err(_) => return Vec::new(),
+    };
+    process_list
+        .into_iter()
+        .filter_map(|process| {
+            let maps = fs::read_to_string(format!("/proc/{}/maps", process.pid)).ok()?;
+            let libraries = deleted_libraries(&maps);
+            if libraries.is_empty() {
+                None
+            } else {
+                Some(StaleProcess {
+                    pid: process.pid,
+                    name: process.stat.comm,
+                    libraries,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Finds shared objects marked ` (deleted)` in the contents of a `/proc/<pid>/maps` file.
+pub fn deleted_libraries(maps: &str) -> Vec<String> {
+    let mut libraries: Vec<String> = maps
+        .lines()
+        .filter_map(|line| {
+            // The path is the sixth column and may itself contain spaces.
+            let path = line.splitn(6, char::is_whitespace).nth(5)?.trim_start();
+            let path = path.strip_suffix(" (deleted)")?;
+            let file_name = path.rsplit('/').next()?;
+            let is_library = file_name.ends_with(".so") || file_name.contains(".so.");
+            // Shared memory and memfd mappings are deleted by design.
+            let is_tmp = path.starts_with("/dev/shm/")
+                || path.starts_with("/memfd:")
+                || path.starts_with("/tmp/")
+                || path.starts_with("/SYSV");
+            if is_library && !is_tmp {
+                Some(path.to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    libraries.sort();
+    libraries.dedup();
+    libraries
+}
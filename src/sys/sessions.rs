@@ -0,0 +1,109 @@
+use super::{
+    username,
+    utmp::{read_utmp, UtmpEntry, UtmpType},
+};
+use crate::services::logind::list_sessions;
+use std::{
+    ffi::CStr,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A user logged in to this machine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    pub user: String,
+    /// Device name of the tty, without the `/dev/` prefix.
+    pub tty: String,
+    /// Remote host for remote logins, empty for local ones.
+    pub host: String,
+    /// Seconds since the epoch.
+    pub login_time: u64,
+    pub idle: Option<Duration>,
+    /// Whether this is the session nmotd is running in.
+    pub current: bool,
+}
+
+/// Lists logged in users from `/var/run/utmp`, asking logind when utmp is missing or empty
+/// (some minimal and container images don't maintain it).
+pub fn sessions() -> Vec<Session> {
+    let entries = read_utmp("/var/run/utmp").unwrap_or_default();
+    let mut sessions = sessions_from_utmp(&entries);
+    if sessions.is_empty() {
+        sessions = sessions_from_logind();
+    }
+
+    let current_tty = current_tty();
+    let current_user = username();
+    for session in &mut sessions {
+        session.current = match &current_tty {
+            Some(tty) => &session.tty == tty,
+            None => session.user == current_user,
+        };
+    }
+    sessions
+}
+
+pub fn sessions_from_utmp(entries: &[UtmpEntry]) -> Vec<Session> {
+    entries
+        .iter()
+        .filter(|entry| entry.type_ == UtmpType::UserProcess && !entry.user.is_empty())
+        .map(|entry| Session {
+            user: entry.user.clone(),
+            tty: entry.line.clone(),
+            host: entry.host.clone(),
+            login_time: entry.time,
+            idle: tty_idle(&entry.line),
+            current: false,
+        })
+        .collect()
+}
+
+fn sessions_from_logind() -> Vec<Session> {
+    let now = now();
+    list_sessions()
+        .map(|sessions| {
+            sessions
+                .into_iter()
+                .map(|session| Session {
+                    user: session.user,
+                    tty: session.tty,
+                    host: session.remote_host,
+                    login_time: session.login_time,
+                    idle: session
+                        .idle_since
+                        .map(|since| Duration::from_secs(now.saturating_sub(since))),
+                    current: false,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Idle time is the time since the tty device was last read from, like `w` reports it.
+fn tty_idle(line: &str) -> Option<Duration> {
+    if line.is_empty() {
+        return None;
+    }
+    let accessed = fs::metadata(Path::new("/dev").join(line))
+        .and_then(|meta| meta.accessed())
+        .ok()?;
+    SystemTime::now().duration_since(accessed).ok()
+}
+
+fn current_tty() -> Option<String> {
+    let mut buf = [0 as libc::c_char; 64];
+    if unsafe { libc::ttyname_r(libc::STDIN_FILENO, buf.as_mut_ptr(), buf.len()) } != 0 {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_string_lossy();
+    Some(name.trim_start_matches("/dev/").to_string())
+}
+
+fn now() -> u64 {
+    UNIX_EPOCH
+        .elapsed()
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,268 @@
+// SMART health of physical disks, from `smartctl --json` or, when smartctl can't be run
+// (it needs root), from the state files smartd keeps in `/var/lib/smartmontools`. Disks
+// that are spun down are left asleep.
+
+use super::disks::{find_type_for_name, DiskType};
+use serde_json::Value;
+use std::{
+    fmt, fs,
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+const SMARTD_STATE_DIR: &str = "/var/lib/smartmontools";
+
+/// How often `SmartMonitor` reads SMART again. The numbers change slowly, and asking a
+/// disk for them isn't free.
+pub const SMART_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// ATA attribute IDs
+const REALLOCATED_SECTORS: u64 = 5;
+const POWER_ON_HOURS: u64 = 9;
+const TEMPERATURE: u64 = 194;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmartHealth {
+    /// `sda` or `nvme0n1` when read through smartctl, the state file's `MODEL-SERIAL`
+    /// otherwise.
+    pub device: String,
+    pub model: String,
+    pub type_: DiskType,
+    /// Overall self-assessment, `None` when only smartd's state file was available.
+    pub passed: Option<bool>,
+    /// Celsius.
+    pub temperature: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    /// NVMe estimate of the rated endurance used up, can go over 100.
+    pub percentage_used: Option<u64>,
+    pub power_on_hours: Option<u64>,
+}
+
+impl SmartHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.passed != Some(false)
+            && self.reallocated_sectors.unwrap_or(0) == 0
+            && self.percentage_used.unwrap_or(0) < 100
+    }
+}
+
+impl fmt::Display for SmartHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.device)?;
+        if !self.model.is_empty() {
+            write!(f, " {}", self.model)?;
+        }
+        match self.passed {
+            Some(true) => write!(f, ": PASSED")?,
+            Some(false) => write!(f, ": FAILED")?,
+            None => write!(f, ":")?,
+        }
+        let mut details = Vec::new();
+        if let Some(temperature) = self.temperature {
+            details.push(format!("{}°C", temperature));
+        }
+        if let Some(reallocated) = self.reallocated_sectors {
+            details.push(format!("{} reallocated", reallocated));
+        }
+        if let Some(used) = self.percentage_used {
+            details.push(format!("{}% used", used));
+        }
+        if let Some(hours) = self.power_on_hours {
+            details.push(format!("{} hours on", hours));
+        }
+        write!(f, " {}", details.join(", "))
+    }
+}
+
+/// Whole disks that may speak SMART, leaving out virtual block devices.
+fn physical_disks() -> Vec<String> {
+    let mut disks: Vec<String> = fs::read_dir("/sys/block")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| {
+                    !["loop", "ram", "zram", "dm-", "md", "sr", "nbd"]
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    disks.sort();
+    disks
+}
+
+/// Keeps the last SMART readings, refreshed every `SMART_INTERVAL`.
+#[derive(Default)]
+pub struct SmartMonitor {
+    pub health: Vec<SmartHealth>,
+    read_at: Option<Instant>,
+}
+
+impl SmartMonitor {
+    pub fn new() -> SmartMonitor {
+        let mut monitor = SmartMonitor::default();
+        monitor.update(Instant::now());
+        monitor
+    }
+
+    /// Reads SMART again unless it was read less than `SMART_INTERVAL` before `now`.
+    /// Disks that are in standby keep their last reading.
+    pub fn update(&mut self, now: Instant) {
+        if let Some(read_at) = self.read_at {
+            if now.duration_since(read_at) < SMART_INTERVAL {
+                return;
+            }
+        }
+        self.health = read_smart_health(&self.health);
+        self.read_at = Some(now);
+    }
+}
+
+/// SMART health of every disk that isn't in standby.
+pub fn smart_health() -> Vec<SmartHealth> {
+    read_smart_health(&[])
+}
+
+/// Reads SMART health, taking the reading of a disk in standby from `previous` instead of
+/// waking it up.
+fn read_smart_health(previous: &[SmartHealth]) -> Vec<SmartHealth> {
+    let from_smartctl: Vec<SmartHealth> = physical_disks()
+        .iter()
+        .filter_map(|disk| {
+            let output = Command::new("smartctl")
+                .args(["--json", "-n", "standby", "-a"])
+                .arg(Path::new("/dev").join(disk))
+                .output()
+                .ok()?;
+            let json = String::from_utf8_lossy(&output.stdout);
+            if is_standby(&json) {
+                return previous
+                    .iter()
+                    .find(|health| &health.device == disk)
+                    .cloned();
+            }
+            parse_smartctl_json(disk, &json)
+        })
+        .collect();
+    if !from_smartctl.is_empty() {
+        return from_smartctl;
+    }
+    let mut from_smartd: Vec<SmartHealth> = fs::read_dir(SMARTD_STATE_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    // smartd.MODEL-SERIAL.ata.state, next to .attrlog.csv files
+                    let name = name.strip_prefix("smartd.")?.strip_suffix(".state")?;
+                    let content = fs::read_to_string(entry.path()).ok()?;
+                    parse_smartd_state(name, &content)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    from_smartd.sort_by(|a, b| a.device.cmp(&b.device));
+    from_smartd
+}
+
+/// Whether smartctl left the disk alone because it was spun down, which `-n standby`
+/// reports as `Device is in STANDBY mode` (or `SLEEP`).
+pub fn is_standby(json: &str) -> bool {
+    let json: Value = match serde_json::from_str(json) {
+        Ok(json) => json,
+        Err(_) => return false,
+    };
+    json["smartctl"]["messages"]
+        .as_array()
+        .map(|messages| {
+            messages.iter().any(|message| {
+                let text = message["string"].as_str().unwrap_or_default();
+                text.contains("STANDBY mode") || text.contains("SLEEP mode")
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the output of `smartctl --json -a /dev/<device>`. `None` when smartctl couldn't
+/// read anything, e.g. because it wasn't run as root or the device doesn't support SMART.
+pub fn parse_smartctl_json(device: &str, json: &str) -> Option<SmartHealth> {
+    let json: Value = serde_json::from_str(json).ok()?;
+    let attribute = |id: u64| {
+        json["ata_smart_attributes"]["table"]
+            .as_array()?
+            .iter()
+            .find(|attribute| attribute["id"].as_u64() == Some(id))?["raw"]["value"]
+            .as_u64()
+    };
+    let nvme = &json["nvme_smart_health_information_log"];
+    let health = SmartHealth {
+        device: device.to_string(),
+        model: json["model_name"].as_str().unwrap_or_default().to_string(),
+        type_: find_type_for_name(device.as_ref()),
+        passed: json["smart_status"]["passed"].as_bool(),
+        temperature: json["temperature"]["current"]
+            .as_u64()
+            .or_else(|| nvme["temperature"].as_u64()),
+        reallocated_sectors: attribute(REALLOCATED_SECTORS),
+        percentage_used: nvme["percentage_used"].as_u64(),
+        power_on_hours: json["power_on_time"]["hours"]
+            .as_u64()
+            .or_else(|| nvme["power_on_hours"].as_u64()),
+    };
+    if health.passed.is_none() && health.temperature.is_none() && health.power_on_hours.is_none() {
+        None
+    } else {
+        Some(health)
+    }
+}
+
+/// Parses a smartd state file, which holds `ata-smart-attribute.N.id` and `.raw` pairs.
+pub fn parse_smartd_state(name: &str, content: &str) -> Option<SmartHealth> {
+    let mut ids = Vec::new();
+    let mut raws = Vec::new();
+    for line in content.lines() {
+        let (key, value) = match line.split_once(" = ") {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        let rest = match key.strip_prefix("ata-smart-attribute.") {
+            Some(rest) => rest,
+            None => continue,
+        };
+        let (index, values) = match rest.split_once('.') {
+            Some((index, "id")) => (index, &mut ids),
+            Some((index, "raw")) => (index, &mut raws),
+            _ => continue,
+        };
+        // An odd value only loses its own attribute
+        if let Ok(value) = value.parse::<u64>() {
+            values.push((index.to_string(), value));
+        }
+    }
+    let raw = |id: u64| {
+        let (index, _) = ids.iter().find(|(_, attribute)| *attribute == id)?;
+        raws.iter()
+            .find(|(raw_index, _)| raw_index == index)
+            .map(|(_, raw)| *raw)
+    };
+    if ids.is_empty() {
+        return None;
+    }
+    // Drop the `.ata` suffix
+    let model = name.rsplit_once('.').map_or(name, |(model, _)| model);
+    Some(SmartHealth {
+        device: model.to_string(),
+        model: String::new(),
+        type_: DiskType::Unknown(-1),
+        passed: None,
+        // The raw value packs min/max temperatures in its upper bytes
+        temperature: raw(TEMPERATURE).map(|raw| raw & 0xff),
+        reallocated_sectors: raw(REALLOCATED_SECTORS),
+        percentage_used: None,
+        // Some drives put minutes or milliseconds in the upper bytes too
+        power_on_hours: raw(POWER_ON_HOURS).map(|raw| raw & 0xffff_ffff),
+    })
+}
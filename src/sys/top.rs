@@ -0,0 +1,163 @@
+// Per-process CPU and memory use, computed from the difference between two reads of
+// `/proc/<pid>/stat`. CPU% is relative to one core, the way top(1) reports it.
+
+use super::{get_uptime, users::user_name};
+use procfs::process::{all_processes, Stat};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessSample {
+    pub pid: i32,
+    pub user: String,
+    /// Command line, or the name in brackets for kernel threads, which have none.
+    pub command: String,
+    pub cpu_percent: f64,
+    /// Resident set size in bytes.
+    pub rss: u64,
+    /// Time since the process started.
+    pub runtime: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProcessSort {
+    Cpu,
+    Memory,
+    Pid,
+}
+
+/// What's needed to turn `/proc/<pid>/stat` clock ticks into wall-clock time.
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    pub uptime: Duration,
+    pub ticks_per_second: u64,
+}
+
+impl Clock {
+    pub fn now() -> Clock {
+        Clock {
+            uptime: get_uptime().unwrap_or_default(),
+            ticks_per_second: procfs::ticks_per_second().unwrap_or(100) as u64,
+        }
+    }
+
+    fn to_duration(self, ticks: u64) -> Duration {
+        Duration::from_millis(ticks * 1000 / self.ticks_per_second.max(1))
+    }
+}
+
+/// Share of one core used by `ticks` of CPU time spent over `elapsed`.
+pub fn cpu_percent(ticks: u64, ticks_per_second: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 || ticks_per_second == 0 {
+        return 0.0;
+    }
+    ticks as f64 / ticks_per_second as f64 / secs * 100.0
+}
+
+/// Builds a sample from a process' stat. Without a `previous` CPU time and the time
+/// since it was taken, CPU% is the average over the process' whole runtime, as ps(1) does.
+pub fn process_sample(
+    stat: &Stat,
+    cmdline: &[String],
+    user: String,
+    previous: Option<(u64, Duration)>,
+    clock: Clock,
+) -> ProcessSample {
+    let ticks = stat.utime + stat.stime;
+    let runtime = clock
+        .uptime
+        .checked_sub(clock.to_duration(stat.starttime))
+        .unwrap_or_default();
+    let cpu_percent = match previous {
+        Some((previous, elapsed)) => cpu_percent(
+            ticks.saturating_sub(previous),
+            clock.ticks_per_second,
+            elapsed,
+        ),
+        None => cpu_percent(ticks, clock.ticks_per_second, runtime),
+    };
+    let command = if cmdline.is_empty() {
+        format!("[{}]", stat.comm)
+    } else {
+        cmdline.join(" ")
+    };
+    ProcessSample {
+        pid: stat.pid,
+        user,
+        command,
+        cpu_percent,
+        rss: stat.rss_bytes().max(0) as u64,
+        runtime,
+    }
+}
+
+/// Busiest first for CPU and memory, ascending for PIDs.
+pub fn sort_processes(processes: &mut [ProcessSample], by: ProcessSort) {
+    match by {
+        ProcessSort::Cpu => processes.sort_by(|a, b| {
+            b.cpu_percent
+                .partial_cmp(&a.cpu_percent)
+                .unwrap_or(Ordering::Equal)
+                .then(b.rss.cmp(&a.rss))
+        }),
+        ProcessSort::Memory => processes.sort_by_key(|process| Reverse(process.rss)),
+        ProcessSort::Pid => processes.sort_by_key(|process| process.pid),
+    }
+}
+
+/// Keeps each process' CPU time from the previous read to turn it into a percentage.
+pub struct ProcessSampler {
+    /// CPU ticks keyed by PID and start time, so a reused PID doesn't inherit them.
+    previous: HashMap<(i32, u64), u64>,
+    sampled_at: Instant,
+    pub processes: Vec<ProcessSample>,
+}
+
+impl ProcessSampler {
+    pub fn new() -> ProcessSampler {
+        let mut sampler = ProcessSampler {
+            previous: HashMap::new(),
+            sampled_at: Instant::now(),
+            processes: Vec::new(),
+        };
+        sampler.sample();
+        sampler
+    }
+
+    pub fn sample(&mut self) {
+        let elapsed = self.sampled_at.elapsed();
+        self.sampled_at = Instant::now();
+        let clock = Clock::now();
+        let mut current = HashMap::new();
+        let mut processes = Vec::new();
+        for process in all_processes().unwrap_or_default() {
+            let stat = &process.stat;
+            let key = (stat.pid, stat.starttime);
+            let previous = self.previous.get(&key).map(|ticks| (*ticks, elapsed));
+            let user = user_name(process.owner).unwrap_or_else(|| process.owner.to_string());
+            let cmdline = process.cmdline().unwrap_or_default();
+            processes.push(process_sample(stat, &cmdline, user, previous, clock));
+            current.insert(key, stat.utime + stat.stime);
+        }
+        self.previous = current;
+        self.processes = processes;
+    }
+
+    /// The `n` processes using the most of `by`.
+    pub fn top(&self, by: ProcessSort, n: usize) -> Vec<ProcessSample> {
+        let mut processes = self.processes.clone();
+        sort_processes(&mut processes, by);
+        processes.truncate(n);
+        processes
+    }
+}
+
+impl Default for ProcessSampler {
+    fn default() -> ProcessSampler {
+        ProcessSampler::new()
+    }
+}
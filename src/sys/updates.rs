@@ -0,0 +1,286 @@
+// Counts pending package updates from the package manager's local metadata. Nothing here
+// touches the network, so the numbers are only as fresh as the last `apt update`,
+// `pacman -Sy` or `dnf makecache`.
+
+use super::OsRelease;
+use std::{cmp::Ordering, collections::HashMap, fmt, fs, path::Path, process::Command};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PackageManager {
+    Apt,
+    Pacman,
+    Dnf,
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageManager::Apt => write!(f, "apt"),
+            PackageManager::Pacman => write!(f, "pacman"),
+            PackageManager::Dnf => write!(f, "dnf"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackageUpdates {
+    pub manager: PackageManager,
+    pub pending: usize,
+    /// `None` when the package manager doesn't tell security updates apart.
+    pub security: Option<usize>,
+}
+
+/// Picks the package manager from `ID`, falling back to each entry of `ID_LIKE`.
+pub fn package_manager(os_release: &OsRelease) -> Option<PackageManager> {
+    std::iter::once(os_release.id.as_str())
+        .chain(os_release.id_like.split_whitespace())
+        .find_map(|id| match id {
+            "debian" | "ubuntu" => Some(PackageManager::Apt),
+            "arch" | "archarm" => Some(PackageManager::Pacman),
+            "fedora" | "rhel" | "centos" => Some(PackageManager::Dnf),
+            _ => None,
+        })
+}
+
+pub fn pending_updates(os_release: &OsRelease) -> Option<PackageUpdates> {
+    match package_manager(os_release)? {
+        PackageManager::Apt => apt_updates(),
+        PackageManager::Pacman => pacman_updates(),
+        PackageManager::Dnf => dnf_updates(),
+    }
+}
+
+/// A package stanza from `/var/lib/dpkg/status` or an apt `Packages` list.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DebPackage {
+    pub name: String,
+    pub arch: String,
+    pub version: String,
+    pub installed: bool,
+}
+
+pub fn parse_deb_packages(content: &str) -> Vec<DebPackage> {
+    let mut packages = Vec::new();
+    let mut package = DebPackage::default();
+    for line in content.lines().chain(std::iter::once("")) {
+        if line.is_empty() {
+            if !package.name.is_empty() {
+                packages.push(package);
+            }
+            package = DebPackage::default();
+        } else if let Some(name) = line.strip_prefix("Package: ") {
+            package.name = name.trim().to_string();
+        } else if let Some(arch) = line.strip_prefix("Architecture: ") {
+            package.arch = arch.trim().to_string();
+        } else if let Some(version) = line.strip_prefix("Version: ") {
+            package.version = version.trim().to_string();
+        } else if line.starts_with("Status: ") {
+            package.installed = line.ends_with(" installed");
+        }
+    }
+    packages
+}
+
+fn apt_updates() -> Option<PackageUpdates> {
+    let status = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+    let installed: Vec<DebPackage> = parse_deb_packages(&status)
+        .into_iter()
+        .filter(|package| package.installed)
+        .collect();
+
+    let mut lists = Vec::new();
+    for entry in fs::read_dir("/var/lib/apt/lists").ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.contains("_Packages") {
+            continue;
+        }
+        if let Some(content) = read_apt_list(&entry.path()) {
+            lists.push((name.contains("security"), parse_deb_packages(&content)));
+        }
+    }
+    let (pending, security) = apt_pending(&installed, &lists);
+    Some(PackageUpdates {
+        manager: PackageManager::Apt,
+        pending,
+        security: Some(security),
+    })
+}
+
+/// apt may store its lists compressed (Docker images default to lz4), so let apt itself
+/// decompress anything that isn't plain text.
+fn read_apt_list(path: &Path) -> Option<String> {
+    if path.extension().is_none() || path.to_string_lossy().ends_with("_Packages") {
+        return fs::read_to_string(path).ok();
+    }
+    let output = Command::new("/usr/lib/apt/apt-helper")
+        .arg("cat-file")
+        .arg(path)
+        .output()
+        .ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// Counts installed packages with a newer version in any list, and how many of those have a
+/// newer version in a security list. `lists` pairs each list with whether it's a security one.
+pub fn apt_pending(installed: &[DebPackage], lists: &[(bool, Vec<DebPackage>)]) -> (usize, usize) {
+    let installed: HashMap<(&str, &str), &str> = installed
+        .iter()
+        .map(|p| ((p.name.as_str(), p.arch.as_str()), p.version.as_str()))
+        .collect();
+    // Whether a newer version came from a security list
+    let mut upgradable: HashMap<(&str, &str), bool> = HashMap::new();
+    for (is_security, packages) in lists {
+        for package in packages {
+            let key = (package.name.as_str(), package.arch.as_str());
+            if let Some(current) = installed.get(&key) {
+                if compare_deb_versions(&package.version, current) == Ordering::Greater {
+                    *upgradable.entry(key).or_insert(false) |= *is_security;
+                }
+            }
+        }
+    }
+    (
+        upgradable.len(),
+        upgradable.values().filter(|security| **security).count(),
+    )
+}
+
+fn deb_order(c: Option<u8>) -> i32 {
+    match c {
+        None => 0,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => i32::from(c),
+        Some(b'~') => -1,
+        Some(c) => i32::from(c) + 256,
+    }
+}
+
+/// dpkg's `verrevcmp`: alternating non-digit and digit runs, where `~` sorts before
+/// everything (even the end of the string) and letters sort before other symbols.
+fn compare_deb_fragment(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let ac = deb_order(a.get(i).copied());
+            let bc = deb_order(b.get(j).copied());
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+        while i < a.len() && a[i] == b'0' {
+            i += 1;
+        }
+        while j < b.len() && b[j] == b'0' {
+            j += 1;
+        }
+        let mut first_diff = Ordering::Equal;
+        while i < a.len() && a[i].is_ascii_digit() && j < b.len() && b[j].is_ascii_digit() {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if i < a.len() && a[i].is_ascii_digit() {
+            return Ordering::Greater;
+        }
+        if j < b.len() && b[j].is_ascii_digit() {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+    Ordering::Equal
+}
+
+fn split_deb_version(version: &str) -> (u64, &str, &str) {
+    let (epoch, rest) = match version.find(':') {
+        Some(pos) => (version[..pos].parse().unwrap_or(0), &version[pos + 1..]),
+        None => (0, version),
+    };
+    match rest.rfind('-') {
+        Some(pos) => (epoch, &rest[..pos], &rest[pos + 1..]),
+        None => (epoch, rest, ""),
+    }
+}
+
+/// Compares two Debian version strings (`[epoch:]upstream[-revision]`) the way dpkg does.
+pub fn compare_deb_versions(a: &str, b: &str) -> Ordering {
+    let (a_epoch, a_upstream, a_revision) = split_deb_version(a);
+    let (b_epoch, b_upstream, b_revision) = split_deb_version(b);
+    a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| compare_deb_fragment(a_upstream, b_upstream))
+        .then_with(|| compare_deb_fragment(a_revision, b_revision))
+}
+
+/// `pacman -Qu` compares the local database against the sync databases without syncing them.
+fn pacman_updates() -> Option<PackageUpdates> {
+    let output = Command::new("pacman").arg("-Qu").output().ok()?;
+    Some(PackageUpdates {
+        manager: PackageManager::Pacman,
+        pending: parse_pacman_updates(&String::from_utf8_lossy(&output.stdout)),
+        security: None,
+    })
+}
+
+/// Lines look like `linux 5.6.3.arch1-1 -> 5.6.4.arch1-1`, ignored packages are marked.
+pub fn parse_pacman_updates(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| line.contains(" -> ") && !line.ends_with("[ignored]"))
+        .count()
+}
+
+/// `dnf -C` only consults the metadata cache.
+fn dnf_updates() -> Option<PackageUpdates> {
+    let output = Command::new("dnf")
+        .args(["-C", "-q", "check-update"])
+        .output()
+        .ok()?;
+    let pending = parse_dnf_check_update(&String::from_utf8_lossy(&output.stdout));
+    let security = Command::new("dnf")
+        .args(["-C", "-q", "updateinfo", "list", "--security"])
+        .output()
+        .ok()
+        .map(|output| parse_dnf_updateinfo(&String::from_utf8_lossy(&output.stdout)));
+    Some(PackageUpdates {
+        manager: PackageManager::Dnf,
+        pending,
+        security,
+    })
+}
+
+/// Counts `name.arch  version  repo` lines, stopping at the "Obsoleting Packages" section.
+pub fn parse_dnf_check_update(output: &str) -> usize {
+    output
+        .lines()
+        .take_while(|line| !line.starts_with("Obsoleting"))
+        .filter(|line| line.split_whitespace().count() == 3 && line.contains('.'))
+        .count()
+}
+
+/// Counts distinct packages in `FEDORA-2020-1234 Important/Sec. name-version.arch` lines.
+pub fn parse_dnf_updateinfo(output: &str) -> usize {
+    let mut packages: Vec<&str> = output
+        .lines()
+        .filter_map(|line| {
+            let mut split = line.split_whitespace();
+            match (split.next(), split.next(), split.next()) {
+                (Some(_), Some(kind), Some(package)) if kind.ends_with("Sec.") => Some(package),
+                _ => None,
+            }
+        })
+        .collect();
+    packages.sort();
+    packages.dedup();
+    packages.len()
+}
@@ -118,6 +118,49 @@ pub fn format_duration(duration: Duration) -> String {
     }
 }
 
+/// Formats seconds since the epoch in local time, like `Mar  4 09:15`.
+pub fn format_timestamp(secs: u64) -> String {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    let mut buf = [0u8; 64];
+    let len = unsafe {
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return String::new();
+        }
+        libc::strftime(
+            buf.as_mut_ptr() as *mut libc::c_char,
+            buf.len(),
+            b"%b %e %H:%M\0".as_ptr() as *const libc::c_char,
+            &tm,
+        )
+    };
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Hour of the day (0-23) in local time of seconds since the epoch.
+pub fn local_hour(secs: u64) -> Option<u32> {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return None;
+    }
+    Some(tm.tm_hour as u32)
+}
+
+/// Compact duration for tables, like `3d`, `2:05` (hours:minutes) or `42s`.
+pub fn format_idle(duration: Duration) -> String {
+    let sec = duration.as_secs();
+    if sec >= 86_400 {
+        format!("{}d", sec / 86_400)
+    } else if sec >= 3_600 {
+        format!("{}:{:02}", sec / 3_600, sec % 3_600 / 60)
+    } else if sec >= 60 {
+        format!("{}m", sec / 60)
+    } else {
+        format!("{}s", sec)
+    }
+}
+
 /*
 fn parse_for_shorthand_time(uptime: String) -> String {
     let newtime = str::replace(&uptime, "years", "y");
@@ -0,0 +1,187 @@
+// Safe lookups in the passwd and group databases, through NSS so LDAP or systemd-homed
+// users resolve too. Results are cached per thread, as collectors ask for the same few
+// ids on every tick. Failed lookups aren't, an LDAP server may be back by the next one.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{CStr, OsStr},
+    io, mem,
+    os::{raw::c_char, unix::ffi::OsStrExt},
+    path::PathBuf,
+    ptr,
+};
+
+/// Buffer size to start with when `sysconf` has no suggestion.
+const DEFAULT_BUF_SIZE: usize = 16_384; // size from the man page
+
+/// Give up growing the buffer past this, an entry that large is surely broken.
+const MAX_BUF_SIZE: usize = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct User {
+    pub uid: u32,
+    pub gid: u32,
+    pub name: String,
+    /// The comment field, conventionally `Full Name,Room,Work phone,Home phone,Other`.
+    pub gecos: String,
+    pub home: PathBuf,
+    pub shell: PathBuf,
+}
+
+impl User {
+    /// The full name from the gecos field, if one is set.
+    pub fn full_name(&self) -> Option<&str> {
+        full_name(&self.gecos)
+    }
+}
+
+/// First comma-separated field of a gecos string, trimmed. `None` when it's empty.
+pub fn full_name(gecos: &str) -> Option<&str> {
+    let name = gecos.split(',').next().unwrap_or("").trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+thread_local! {
+    static USERS: RefCell<HashMap<u32, Option<User>>> = RefCell::new(HashMap::new());
+    static GROUPS: RefCell<HashMap<u32, Option<String>>> = RefCell::new(HashMap::new());
+}
+
+/// The passwd entry of `uid`, or `None` when there isn't one or the lookup failed.
+pub fn user(uid: u32) -> Option<User> {
+    USERS.with(|users| cached(&mut users.borrow_mut(), uid, getpwuid))
+}
+
+/// Login name of `uid`.
+pub fn user_name(uid: u32) -> Option<String> {
+    user(uid).map(|user| user.name)
+}
+
+/// Name of the group `gid`.
+pub fn group_name(gid: u32) -> Option<String> {
+    GROUPS.with(|groups| cached(&mut groups.borrow_mut(), gid, getgrgid))
+}
+
+/// The entry of `id` in `cache`, looked up and cached when missing. Errors aren't cached,
+/// only a lookup that found nothing is.
+pub fn cached<T, F>(cache: &mut HashMap<u32, Option<T>>, id: u32, lookup: F) -> Option<T>
+where
+    T: Clone,
+    F: FnOnce(u32) -> io::Result<Option<T>>,
+{
+    if let Some(entry) = cache.get(&id) {
+        return entry.clone();
+    }
+    let entry = lookup(id).ok()?;
+    cache.insert(id, entry.clone());
+    entry
+}
+
+pub fn euid() -> u32 {
+    unsafe { libc::geteuid() }
+}
+
+/// The user this process runs as.
+pub fn current_user() -> Option<User> {
+    user(euid())
+}
+
+/// Login name of the user this process runs as, or an empty string if it has none.
+pub fn username() -> String {
+    user_name(euid()).unwrap_or_default()
+}
+
+/// Runs a reentrant `get*_r` lookup, growing the buffer while it reports `ERANGE`.
+/// Returns `None` when there's no entry and the call's error when it failed.
+fn lookup<T, F>(sysconf_name: libc::c_int, mut call: F) -> io::Result<Option<T>>
+where
+    F: FnMut(*mut T, &mut [c_char], *mut *mut T) -> libc::c_int,
+{
+    let suggested = unsafe { libc::sysconf(sysconf_name) };
+    let mut size = if suggested > 0 {
+        suggested as usize
+    } else {
+        DEFAULT_BUF_SIZE
+    };
+    loop {
+        let mut buffer = vec![0 as c_char; size];
+        let mut entry = mem::MaybeUninit::<T>::uninit();
+        let mut result: *mut T = ptr::null_mut();
+        let ret = call(entry.as_mut_ptr(), &mut buffer, &mut result);
+        if ret == libc::ERANGE && size < MAX_BUF_SIZE {
+            size *= 2;
+            continue;
+        }
+        if ret != 0 {
+            return Err(io::Error::from_raw_os_error(ret));
+        }
+        if result.is_null() {
+            return Ok(None);
+        }
+        // The call filled in `entry` and pointed `result` at it.
+        return Ok(Some(unsafe { entry.assume_init() }));
+    }
+}
+
+fn getpwuid(uid: u32) -> io::Result<Option<User>> {
+    let mut strings = None;
+    let passwd = lookup::<libc::passwd, _>(libc::_SC_GETPW_R_SIZE_MAX, |entry, buffer, result| {
+        let ret =
+            unsafe { libc::getpwuid_r(uid, entry, buffer.as_mut_ptr(), buffer.len(), result) };
+        // The entry's strings point into `buffer`, so copy them out before it's dropped.
+        if ret == 0 && !unsafe { *result }.is_null() {
+            let entry = unsafe { &*entry };
+            strings = Some(unsafe {
+                (
+                    string(entry.pw_name),
+                    string(entry.pw_gecos),
+                    path(entry.pw_dir),
+                    path(entry.pw_shell),
+                )
+            });
+        }
+        ret
+    })?;
+    Ok(passwd
+        .zip(strings)
+        .map(|(passwd, (name, gecos, home, shell))| User {
+            uid: passwd.pw_uid,
+            gid: passwd.pw_gid,
+            name,
+            gecos,
+            home,
+            shell,
+        }))
+}
+
+fn getgrgid(gid: u32) -> io::Result<Option<String>> {
+    let mut name = None;
+    lookup::<libc::group, _>(libc::_SC_GETGR_R_SIZE_MAX, |entry, buffer, result| {
+        let ret =
+            unsafe { libc::getgrgid_r(gid, entry, buffer.as_mut_ptr(), buffer.len(), result) };
+        if ret == 0 && !unsafe { *result }.is_null() {
+            name = Some(unsafe { string((*entry).gr_name) });
+        }
+        ret
+    })?;
+    Ok(name)
+}
+
+/// Copies a C string from a passwd or group entry. Null becomes an empty string.
+unsafe fn string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+unsafe fn path(ptr: *const c_char) -> PathBuf {
+    if ptr.is_null() {
+        return PathBuf::new();
+    }
+    PathBuf::from(OsStr::from_bytes(CStr::from_ptr(ptr).to_bytes()))
+}
@@ -0,0 +1,121 @@
+// Parser for the glibc `struct utmp` records found in `/var/run/utmp`, `/var/log/wtmp`
+// and `/var/log/btmp`. See utmp(5).
+
+use std::{
+    convert::TryInto,
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
+
+/// Size of a single record on 64-bit Linux (glibc keeps `ut_tv` 32-bit for compatibility).
+pub const UTMP_RECORD_SIZE: usize = 384;
+
+const UT_LINESIZE: usize = 32;
+const UT_NAMESIZE: usize = 32;
+const UT_HOSTSIZE: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UtmpType {
+    Empty,
+    RunLevel,
+    BootTime,
+    NewTime,
+    OldTime,
+    InitProcess,
+    LoginProcess,
+    UserProcess,
+    DeadProcess,
+    Accounting,
+    Unknown(i16),
+}
+
+impl From<i16> for UtmpType {
+    fn from(t: i16) -> UtmpType {
+        match t {
+            0 => UtmpType::Empty,
+            1 => UtmpType::RunLevel,
+            2 => UtmpType::BootTime,
+            3 => UtmpType::NewTime,
+            4 => UtmpType::OldTime,
+            5 => UtmpType::InitProcess,
+            6 => UtmpType::LoginProcess,
+            7 => UtmpType::UserProcess,
+            8 => UtmpType::DeadProcess,
+            9 => UtmpType::Accounting,
+            t => UtmpType::Unknown(t),
+        }
+    }
+}
+
+/// A single utmp/wtmp/btmp record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UtmpEntry {
+    pub type_: UtmpType,
+    pub pid: i32,
+    /// Device name of the tty, without the `/dev/` prefix.
+    pub line: String,
+    pub user: String,
+    /// Remote host for remote logins, empty for local ones.
+    pub host: String,
+    pub session: i32,
+    /// Seconds since the epoch.
+    pub time: u64,
+    /// Remote address, if the login program recorded one.
+    pub addr: Option<IpAddr>,
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn i32_at(record: &[u8], offset: usize) -> i32 {
+    i32::from_ne_bytes(record[offset..offset + 4].try_into().unwrap())
+}
+
+fn parse_addr(bytes: &[u8]) -> Option<IpAddr> {
+    if bytes.iter().all(|&b| b == 0) {
+        None
+    } else if bytes[4..].iter().all(|&b| b == 0) {
+        // IPv4 addresses only occupy the first word, in network byte order.
+        Some(IpAddr::V4(Ipv4Addr::new(
+            bytes[0], bytes[1], bytes[2], bytes[3],
+        )))
+    } else {
+        let octets: [u8; 16] = bytes.try_into().unwrap();
+        Some(IpAddr::V6(Ipv6Addr::from(octets)))
+    }
+}
+
+/// Parses one `UTMP_RECORD_SIZE` byte record.
+pub fn parse_record(record: &[u8]) -> UtmpEntry {
+    let line = 8;
+    let id = line + UT_LINESIZE;
+    let user = id + 4;
+    let host = user + UT_NAMESIZE;
+    let session = host + UT_HOSTSIZE + 4; // skip ut_exit
+    let tv_sec = session + 4;
+    let addr = tv_sec + 8;
+    UtmpEntry {
+        type_: UtmpType::from(i16::from_ne_bytes([record[0], record[1]])),
+        pid: i32_at(record, 4),
+        line: c_string(&record[line..id]),
+        user: c_string(&record[user..host]),
+        host: c_string(&record[host..host + UT_HOSTSIZE]),
+        session: i32_at(record, session),
+        time: i32_at(record, tv_sec) as u32 as u64,
+        addr: parse_addr(&record[addr..addr + 16]),
+    }
+}
+
+/// Parses a buffer of consecutive records, ignoring a trailing partial record.
+pub fn parse_utmp(data: &[u8]) -> Vec<UtmpEntry> {
+    data.chunks_exact(UTMP_RECORD_SIZE)
+        .map(parse_record)
+        .collect()
+}
+
+pub fn read_utmp<P: AsRef<Path>>(path: P) -> io::Result<Vec<UtmpEntry>> {
+    Ok(parse_utmp(&fs::read(path)?))
+}
@@ -0,0 +1,171 @@
+// Virtualization and container detection, along the lines of `systemd-detect-virt`.
+// Containers are checked first since they can run inside a VM.
+
+use std::{fmt, fs, path::Path};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Virt {
+    BareMetal,
+    /// Hypervisor name, e.g. `KVM` or `VMware`.
+    Vm(String),
+    /// Container manager, e.g. `Docker` or `LXC`.
+    Container(String),
+    /// Windows Subsystem for Linux, version 1 or 2.
+    Wsl(u8),
+}
+
+impl Virt {
+    pub fn is_container(&self) -> bool {
+        matches!(self, Virt::Container(_))
+    }
+
+    /// Whether the hardware nmotd sees is real, which SMART data for instance depends on.
+    pub fn is_bare_metal(&self) -> bool {
+        *self == Virt::BareMetal
+    }
+}
+
+impl fmt::Display for Virt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Virt::BareMetal => write!(f, "bare metal"),
+            Virt::Vm(hypervisor) => write!(f, "{} guest", hypervisor),
+            Virt::Container(manager) => write!(f, "{} container", manager),
+            Virt::Wsl(version) => write!(f, "WSL{}", version),
+        }
+    }
+}
+
+/// Everything detection looks at, gathered up front so it can be tested.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VirtProbe {
+    /// `sys_vendor`, `product_name`, `board_vendor` and `bios_vendor` from
+    /// `/sys/class/dmi/id`.
+    pub dmi: Vec<String>,
+    /// The `hypervisor` CPU flag from `/proc/cpuinfo`.
+    pub cpu_hypervisor: bool,
+    /// `/sys/hypervisor/type`, set by Xen.
+    pub hypervisor_type: Option<String>,
+    pub pid1_cgroup: String,
+    pub dockerenv: bool,
+    pub containerenv: bool,
+    /// `/run/systemd/container`, written by systemd-nspawn and LXC among others.
+    pub systemd_container: Option<String>,
+    /// `/proc/sys/kernel/osrelease`.
+    pub kernel_release: String,
+}
+
+impl VirtProbe {
+    pub fn read() -> VirtProbe {
+        let read = |path: &str| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+        let dmi = ["sys_vendor", "product_name", "board_vendor", "bios_vendor"]
+            .iter()
+            .filter_map(|file| read(&format!("/sys/class/dmi/id/{}", file)))
+            .collect();
+        let cpu_hypervisor = fs::read_to_string("/proc/cpuinfo")
+            .map(|cpuinfo| {
+                cpuinfo
+                    .lines()
+                    .filter(|line| line.starts_with("flags"))
+                    .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor"))
+            })
+            .unwrap_or(false);
+        VirtProbe {
+            dmi,
+            cpu_hypervisor,
+            hypervisor_type: read("/sys/hypervisor/type"),
+            pid1_cgroup: read("/proc/1/cgroup").unwrap_or_default(),
+            dockerenv: Path::new("/.dockerenv").exists(),
+            containerenv: Path::new("/run/.containerenv").exists(),
+            systemd_container: read("/run/systemd/container"),
+            kernel_release: read("/proc/sys/kernel/osrelease").unwrap_or_default(),
+        }
+    }
+
+    pub fn detect(&self) -> Virt {
+        // WSL2's kernel is `*-microsoft-standard*`, WSL1 fakes `*-Microsoft`
+        if self.kernel_release.contains("microsoft") {
+            return Virt::Wsl(2);
+        }
+        if self.kernel_release.contains("Microsoft") {
+            return Virt::Wsl(1);
+        }
+        if let Some(container) = self.container() {
+            return Virt::Container(container.to_string());
+        }
+        for value in &self.dmi {
+            if let Some(hypervisor) = dmi_hypervisor(value) {
+                return Virt::Vm(hypervisor.to_string());
+            }
+        }
+        if let Some(hypervisor) = &self.hypervisor_type {
+            if hypervisor == "xen" {
+                return Virt::Vm("Xen".to_string());
+            }
+        }
+        if self.cpu_hypervisor {
+            // Some hypervisor that doesn't identify itself through DMI, like Firecracker
+            return Virt::Vm("Unknown".to_string());
+        }
+        Virt::BareMetal
+    }
+
+    fn container(&self) -> Option<&'static str> {
+        if self.containerenv {
+            return Some("Podman");
+        }
+        if self.dockerenv {
+            return Some("Docker");
+        }
+        if let Some(container) = &self.systemd_container {
+            return Some(match container.as_str() {
+                "docker" => "Docker",
+                "podman" => "Podman",
+                "lxc" | "lxc-libvirt" => "LXC",
+                "systemd-nspawn" => "systemd-nspawn",
+                "rkt" => "rkt",
+                "wsl" => "WSL",
+                _ => "Unknown",
+            });
+        }
+        // cgroup v1 paths name the container runtime, v2 mostly shows `0::/`
+        let cgroup = &self.pid1_cgroup;
+        if cgroup.contains("kubepods") {
+            Some("Kubernetes")
+        } else if cgroup.contains("/docker") {
+            Some("Docker")
+        } else if cgroup.contains("/lxc") {
+            Some("LXC")
+        } else {
+            None
+        }
+    }
+}
+
+fn dmi_hypervisor(value: &str) -> Option<&'static str> {
+    const VENDORS: [(&str, &str); 11] = [
+        ("KVM", "KVM"),
+        ("QEMU", "QEMU"),
+        ("VMware", "VMware"),
+        ("VMW", "VMware"),
+        ("innotek GmbH", "VirtualBox"),
+        ("VirtualBox", "VirtualBox"),
+        ("Xen", "Xen"),
+        ("Amazon EC2", "Amazon EC2"),
+        ("Google Compute Engine", "Google Compute Engine"),
+        ("Parallels", "Parallels"),
+        ("BHYVE", "bhyve"),
+    ];
+    if value == "Virtual Machine" {
+        // Hyper-V's product name, its vendor is plain "Microsoft Corporation"
+        return Some("Hyper-V");
+    }
+    VENDORS
+        .iter()
+        .find(|(prefix, _)| value.starts_with(prefix))
+        .map(|(_, hypervisor)| *hypervisor)
+}
+
+pub fn virt() -> Virt {
+    VirtProbe::read().detect()
+}
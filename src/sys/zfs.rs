@@ -0,0 +1,186 @@
+// ZFS pools, from the SPL kstats and the `zpool` command. statvfs on a dataset only sees
+// that dataset's share of the pool, so the pool itself is reported separately.
+
+use super::MemUnit;
+use std::{collections::HashMap, fmt, fs, path::Path, process::Command};
+
+const KSTAT_DIR: &str = "/proc/spl/kstat/zfs";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ZfsPool {
+    pub name: String,
+    /// `ONLINE`, `DEGRADED`, `FAULTED`, `OFFLINE`, `UNAVAIL` or `REMOVED`.
+    pub state: String,
+    /// Bytes.
+    pub size: u64,
+    pub allocated: u64,
+    pub free: u64,
+    /// Percentage of fragmented free space, `None` without the spacemap_histogram feature.
+    pub fragmentation: Option<u64>,
+    pub capacity: u64,
+    /// The `scan:` line of `zpool status`, e.g. the last scrub or the running resilver.
+    pub scan: Option<String>,
+    /// The `errors:` line of `zpool status`.
+    pub errors: Option<String>,
+}
+
+impl ZfsPool {
+    pub fn is_healthy(&self) -> bool {
+        self.state == "ONLINE"
+            && match &self.errors {
+                Some(errors) => errors == "No known data errors",
+                None => true,
+            }
+    }
+}
+
+impl fmt::Display for ZfsPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.state)?;
+        if self.size > 0 {
+            write!(
+                f,
+                ", {}% of {} used",
+                self.capacity,
+                MemUnit::from_bytes(self.size)
+            )?;
+        }
+        if let Some(fragmentation) = self.fragmentation {
+            write!(f, ", {}% fragmented", fragmentation)?;
+        }
+        if let Some(scan) = &self.scan {
+            write!(f, ", {}", scan)?;
+        }
+        match &self.errors {
+            Some(errors) if errors != "No known data errors" => write!(f, ", {}", errors),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Pools known to the kernel module, with their state. Empty when ZFS isn't loaded.
+pub fn pool_states() -> HashMap<String, String> {
+    let entries = match fs::read_dir(KSTAT_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return HashMap::new(),
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            // Only pools have a `state` file, next to global kstats like `arcstats`
+            let state = fs::read_to_string(entry.path().join("state")).ok()?;
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                state.trim().to_string(),
+            ))
+        })
+        .collect()
+}
+
+pub fn zfs_pools() -> Vec<ZfsPool> {
+    // Don't go looking for `zpool` on hosts without ZFS
+    if !Path::new(KSTAT_DIR).exists() {
+        return Vec::new();
+    }
+    let states = pool_states();
+    let mut pools = Command::new("zpool")
+        .args(["list", "-Hp", "-o", "name,size,alloc,free,frag,cap,health"])
+        .output()
+        .map(|output| parse_zpool_list(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default();
+    for (name, state) in &states {
+        if !pools.iter().any(|pool| &pool.name == name) {
+            pools.push(ZfsPool {
+                name: name.clone(),
+                state: state.clone(),
+                ..ZfsPool::default()
+            });
+        }
+    }
+    if let Ok(output) = Command::new("zpool").arg("status").output() {
+        let status = parse_zpool_status(&String::from_utf8_lossy(&output.stdout));
+        for pool in &mut pools {
+            if let Some((scan, errors)) = status.get(&pool.name) {
+                pool.scan = scan.clone();
+                pool.errors = errors.clone();
+            }
+        }
+    }
+    for pool in &mut pools {
+        if let Some(state) = states.get(&pool.name) {
+            pool.state = state.clone();
+        }
+    }
+    pools.sort_by(|a, b| a.name.cmp(&b.name));
+    pools
+}
+
+/// Parses the tab separated output of `zpool list -Hp -o name,size,alloc,free,frag,cap,health`.
+pub fn parse_zpool_list(output: &str) -> Vec<ZfsPool> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 7 {
+                return None;
+            }
+            let number = |field: &str| field.trim_end_matches('%').parse::<u64>().ok();
+            Some(ZfsPool {
+                name: fields[0].to_string(),
+                size: number(fields[1])?,
+                allocated: number(fields[2])?,
+                free: number(fields[3])?,
+                // `-` when the pool can't tell
+                fragmentation: number(fields[4]),
+                capacity: number(fields[5])?,
+                state: fields[6].to_string(),
+                scan: None,
+                errors: None,
+            })
+        })
+        .collect()
+}
+
+type ScanAndErrors = (Option<String>, Option<String>);
+
+/// Picks each pool's `scan:` and `errors:` lines out of `zpool status`. The scan line may
+/// continue on the following lines while a scrub or resilver is running.
+pub fn parse_zpool_status(output: &str) -> HashMap<String, ScanAndErrors> {
+    let mut pools = HashMap::new();
+    let mut pool: Option<String> = None;
+    let mut in_scan = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let field = trimmed.split_once(": ");
+        match field {
+            Some(("pool", name)) => {
+                pool = Some(name.to_string());
+                pools.insert(name.to_string(), (None, None));
+                in_scan = false;
+                continue;
+            }
+            Some((key, _)) if !key.contains(' ') => in_scan = false,
+            _ if trimmed == "config:" || trimmed.is_empty() => in_scan = false,
+            _ => {}
+        }
+        let entry = match pool.as_ref().and_then(|name| pools.get_mut(name)) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        match field {
+            Some(("scan", scan)) => {
+                entry.0 = Some(scan.to_string());
+                in_scan = true;
+            }
+            Some(("errors", errors)) => entry.1 = Some(errors.to_string()),
+            _ if in_scan => {
+                if let Some(scan) = &mut entry.0 {
+                    scan.push_str(", ");
+                    scan.push_str(trimmed);
+                }
+            }
+            _ => {}
+        }
+    }
+    pools
+}
@@ -0,0 +1,56 @@
+use std::fs;
+
+/// Compression statistics of a zram device, from `/sys/block/zram*/mm_stat`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Zram {
+    pub name: String,
+    /// Uncompressed size of the data stored, in bytes.
+    pub orig_data_size: u64,
+    /// Compressed size of the data stored, in bytes.
+    pub compr_data_size: u64,
+    /// Memory allocated for the device including allocator overhead, in bytes.
+    pub mem_used_total: u64,
+}
+
+impl Zram {
+    /// How many times smaller the data got, counting allocator overhead.
+    pub fn ratio(&self) -> f64 {
+        if self.mem_used_total == 0 {
+            0.0
+        } else {
+            self.orig_data_size as f64 / self.mem_used_total as f64
+        }
+    }
+}
+
+/// Parses the first three columns of `mm_stat`; the rest differ between kernel versions.
+pub fn parse_mm_stat(name: &str, content: &str) -> Option<Zram> {
+    let mut fields = content.split_whitespace().map(|x| x.parse::<u64>());
+    Some(Zram {
+        name: name.to_string(),
+        orig_data_size: fields.next()?.ok()?,
+        compr_data_size: fields.next()?.ok()?,
+        mem_used_total: fields.next()?.ok()?,
+    })
+}
+
+/// Devices that hold no data yet are left out, their ratio means nothing.
+pub fn zram_devices() -> Vec<Zram> {
+    let mut devices: Vec<Zram> = fs::read_dir("/sys/block")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if !name.starts_with("zram") {
+                        return None;
+                    }
+                    let content = fs::read_to_string(entry.path().join("mm_stat")).ok()?;
+                    parse_mm_stat(&name, &content).filter(|zram| zram.orig_data_size > 0)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
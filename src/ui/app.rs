@@ -1,9 +1,21 @@
+use super::theme::Theme;
+use crate::colors::ColorDepth;
+use crate::logos::ShownLogo;
 use crate::services::{get_docker_processes, list_unit_files, systemd::*};
 use crate::util::{RandomSignal, SinSignal, StatefulList, TabsState};
+use std::time::Instant;
+use tui::{layout::Rect, widgets::TableState};
 
 use crate::sys::{
-    cpu_info, get_all_disks, get_kernel, hostname, loadavg, mem_info, process_by_user, uptime,
-    MemUnit, OsRelease,
+    btrfs::BtrfsFilesystem, btrfs_filesystems, connections_by_remote, cpu_info, disks::Disk,
+    dmi::HostInfo, get_disks, get_kernel, greeting::Greeting, host_info, hostname, listening_ports,
+    loadavg, login_summary, logins::LoginSummary, md_arrays, mdstat::MdArray, mem_info,
+    net::ListeningPort, pending_updates, power::PowerStatus, power_status, pressure,
+    pressure::Pressure, process::ProcessByUser, process_by_user, reboot::RebootReason,
+    reboot_required, sessions, sessions::Session, top, updates::PackageUpdates, uptime, virt,
+    zfs::ZfsPool, zfs_pools, zram::Zram, zram_devices, BlockedWatch, DiskFilter, DiskIoSampler,
+    DiskThresholds, GreetingFacts, MemInfo, MemUnit, OsRelease, ProcessSampler, ProcessSort,
+    SmartMonitor, Virt,
 };
 
 const TASKS: [&'static str; 24] = [
@@ -41,6 +53,9 @@ const LOGS: [(&'static str, &'static str); 26] = [
     ("Event26", "INFO"),
 ];
 
+/// Index of the process table in `App::tabs`.
+pub const PROCESSES_TAB: usize = 1;
+
 pub struct Signal<S: Iterator> {
     source: S,
     pub points: Vec<S::Item>,
@@ -92,17 +107,56 @@ pub struct App<'a> {
     pub tasks: StatefulList<&'a str>,
     pub logs: StatefulList<(&'a str, &'a str)>,
     pub signals: Signals,
-    pub barchart: Vec<(&'a str, u64)>,
+    pub barchart: Vec<(String, u64)>,
     pub servers: Vec<Server<'a>>,
     pub enhanced_graphics: bool,
     pub wanted_systemd_units: Vec<SystemdUnit>,
+    pub listening_ports: Vec<ListeningPort>,
+    pub sessions: Vec<Session>,
+    pub logins: LoginSummary,
+    pub updates: Option<PackageUpdates>,
+    pub reboot_reasons: Vec<RebootReason>,
+    pub memory: MemInfo,
+    pub pressure: Pressure,
+    pub zram: Vec<Zram>,
+    pub disk_filter: DiskFilter,
+    pub disks: Vec<Disk>,
+    pub disk_thresholds: DiskThresholds,
+    pub disk_io: DiskIoSampler,
+    pub zfs_pools: Vec<ZfsPool>,
+    pub btrfs: Vec<BtrfsFilesystem>,
+    pub md_arrays: Vec<MdArray>,
+    pub smart: SmartMonitor,
+    pub power: PowerStatus,
+    pub virt: Virt,
+    pub host: HostInfo,
+    pub process_states: ProcessByUser,
+    pub blocked: BlockedWatch,
+    pub processes: ProcessSampler,
+    pub process_sort: ProcessSort,
+    pub process_table: TableState,
+    pub theme: Theme,
+    pub color_depth: ColorDepth,
+    pub greeting: Greeting,
+    pub greeting_facts: GreetingFacts,
+    pub logo: Option<ShownLogo>,
+    /// Where the logo pane was last drawn, for painting an image logo over it.
+    pub logo_area: Option<Rect>,
 }
 
 impl<'a> App<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         title: &'a str,
         enhanced_graphics: bool,
         wanted_systemd_units: Vec<&str>,
+        disk_filter: DiskFilter,
+        disk_thresholds: DiskThresholds,
+        show_serial: bool,
+        theme: Option<Theme>,
+        color_depth: ColorDepth,
+        greeting: Greeting,
+        logo: Option<ShownLogo>,
     ) -> App<'a> {
         let mut rand_signal = RandomSignal::new(0, 100);
         let sparkline_points = rand_signal.by_ref().take(300).collect();
@@ -111,34 +165,40 @@ impl<'a> App<'a> {
         let mut sin_signal2 = SinSignal::new(0.1, 2.0, 10.0);
         let sin2_points = sin_signal2.by_ref().take(200).collect();
         let mem = mem_info().unwrap();
+        let virt = virt();
         let load = loadavg().unwrap();
         let pbu = process_by_user();
+        let logins = login_summary();
+        let os_release = OsRelease::new().ok();
+        let mut processes = ProcessSampler::new();
+        top::sort_processes(&mut processes.processes, ProcessSort::Cpu);
         let mut important_units = Vec::with_capacity(wanted_systemd_units.len());
         let mut bars = Vec::with_capacity(10);
-        bars.push(("Load(1m)", (load.one * 100_f64).ceil() as u64));
-        bars.push(("Load(5m)", (load.five * 100_f64).ceil() as u64));
-        bars.push(("Load(15m)", (load.fifteen * 100_f64).ceil() as u64));
+        bars.push(("Load(1m)".to_string(), (load.one * 100_f64).ceil() as u64));
+        bars.push(("Load(5m)".to_string(), (load.five * 100_f64).ceil() as u64));
         bars.push((
-            "Memory",
-            ((mem.total - mem.free - mem.cached - mem.buffers - mem.sreclaimable) as f64
-                / mem.total as f64
-                * 100_f64)
-                .ceil() as u64,
+            "Load(15m)".to_string(),
+            (load.fifteen * 100_f64).ceil() as u64,
         ));
+        bars.push(("RAM used".to_string(), mem.used_percent().ceil() as u64));
         if mem.swap_total > 0 {
             bars.push((
-                "Swap",
-                (mem.swap_free as f64 / mem.swap_total as f64 * 100_f64).ceil() as u64,
+                "Swap used".to_string(),
+                mem.swap_used_percent().ceil() as u64,
             ));
         }
         bars.push((
-            "Root Proc",
+            "Root Proc".to_string(),
             (pbu.root as f64 / pbu.all as f64 * 100_f64).ceil() as u64,
         ));
         bars.push((
-            "User Proc",
+            "User Proc".to_string(),
             (pbu.user as f64 / pbu.all as f64 * 100_f64).ceil() as u64,
         ));
+        // Established connections for the five busiest remote addresses
+        for remote in connections_by_remote().into_iter().take(5) {
+            bars.push((remote.address.to_string(), remote.count as u64));
+        }
         for sd_unit in list_unit_files().unwrap() {
             if !sd_unit.name.is_empty() && wanted_systemd_units.contains(&sd_unit.name.as_str()) {
                 important_units.push(sd_unit);
@@ -147,7 +207,7 @@ impl<'a> App<'a> {
         App {
             title,
             should_quit: false,
-            tabs: TabsState::new(vec!["Tab0", "Tab1"]),
+            tabs: TabsState::new(vec!["Overview", "Processes"]),
             show_chart: true,
             progress: 0.0,
             sparkline: Signal {
@@ -199,15 +259,61 @@ impl<'a> App<'a> {
             ],
             enhanced_graphics,
             wanted_systemd_units: important_units,
+            listening_ports: listening_ports(),
+            sessions: sessions(),
+            theme: theme
+                .unwrap_or_else(|| Theme::for_distro(os_release.as_ref()))
+                .degrade(color_depth),
+            color_depth,
+            greeting_facts: GreetingFacts::gather(logins.last_login.as_ref()),
+            greeting,
+            logins,
+            logo,
+            logo_area: None,
+            updates: os_release.as_ref().and_then(pending_updates),
+            reboot_reasons: reboot_required(),
+            memory: mem,
+            pressure: pressure(),
+            zram: zram_devices(),
+            disks: get_disks(&disk_filter),
+            disk_filter,
+            disk_thresholds,
+            disk_io: DiskIoSampler::new(),
+            zfs_pools: zfs_pools(),
+            btrfs: btrfs_filesystems(),
+            md_arrays: md_arrays(),
+            smart: smart_for(&virt),
+            power: power_for(&virt),
+            virt,
+            host: host_info(show_serial),
+            blocked: blocked_watch(&pbu),
+            process_states: pbu,
+            processes,
+            process_sort: ProcessSort::Cpu,
+            process_table: TableState::default(),
         }
     }
 
     pub fn on_up(&mut self) {
-        self.tasks.previous();
+        if self.tabs.index == PROCESSES_TAB {
+            let selected = self.process_table.selected().unwrap_or(0);
+            self.process_table.select(Some(selected.saturating_sub(1)));
+        } else {
+            self.tasks.previous();
+        }
     }
 
     pub fn on_down(&mut self) {
-        self.tasks.next();
+        if self.tabs.index == PROCESSES_TAB {
+            let last = self.processes.processes.len().saturating_sub(1);
+            let selected = match self.process_table.selected() {
+                Some(selected) => (selected + 1).min(last),
+                None => 0,
+            };
+            self.process_table.select(Some(selected));
+        } else {
+            self.tasks.next();
+        }
     }
 
     pub fn on_right(&mut self) {
@@ -226,6 +332,10 @@ impl<'a> App<'a> {
             't' => {
                 self.show_chart = !self.show_chart;
             }
+            '\t' => self.on_right(),
+            'c' => self.sort_processes(ProcessSort::Cpu),
+            'm' => self.sort_processes(ProcessSort::Memory),
+            'p' => self.sort_processes(ProcessSort::Pid),
             _ => {}
         }
     }
@@ -245,5 +355,59 @@ impl<'a> App<'a> {
 
         let event = self.barchart.pop().unwrap();
         self.barchart.insert(0, event);
+
+        self.listening_ports = listening_ports();
+        self.sessions = sessions();
+        self.reboot_reasons = reboot_required();
+        if let Ok(memory) = mem_info() {
+            self.memory = memory;
+        }
+        self.pressure = pressure();
+        self.zram = zram_devices();
+        self.disks = get_disks(&self.disk_filter);
+        self.disk_io.sample();
+        self.zfs_pools = zfs_pools();
+        self.btrfs = btrfs_filesystems();
+        self.md_arrays = md_arrays();
+        if self.virt.is_bare_metal() {
+            self.smart.update(Instant::now());
+        }
+        self.power = power_for(&self.virt);
+        self.process_states = process_by_user();
+        self.blocked
+            .update(&self.process_states.blocked, Instant::now());
+        self.processes.sample();
+        self.greeting_facts = GreetingFacts::gather(self.logins.last_login.as_ref());
+        top::sort_processes(&mut self.processes.processes, self.process_sort);
+    }
+
+    fn sort_processes(&mut self, by: ProcessSort) {
+        self.process_sort = by;
+        top::sort_processes(&mut self.processes.processes, by);
+        self.process_table.select(Some(0));
+    }
+}
+
+fn blocked_watch(processes: &ProcessByUser) -> BlockedWatch {
+    let mut watch = BlockedWatch::default();
+    watch.update(&processes.blocked, Instant::now());
+    watch
+}
+
+/// Virtual disks don't have SMART data worth showing.
+fn smart_for(virt: &Virt) -> SmartMonitor {
+    if virt.is_bare_metal() {
+        SmartMonitor::new()
+    } else {
+        SmartMonitor::default()
+    }
+}
+
+/// Containers see the host's batteries, which aren't theirs to worry about.
+fn power_for(virt: &Virt) -> PowerStatus {
+    if virt.is_container() {
+        PowerStatus::default()
+    } else {
+        power_status()
     }
 }
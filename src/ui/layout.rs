@@ -1,6 +1,6 @@
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
     widgets::{
@@ -10,52 +10,210 @@ use tui::{
     Frame,
 };
 
+use crate::colors::ColorDepth;
+use crate::logos::{Logo, ShownLogo};
 use crate::quotes::get_quote;
 use crate::services::{get_docker_processes, systemd::*};
-use crate::sys::{get_all_disks, MemUnit};
-use crate::ui::App;
+use crate::sys::{format_idle, format_timestamp, greeting::render, MemUnit, ProcessSort};
+use crate::ui::{
+    theme::{degrade, degrade_style},
+    App, Theme, PROCESSES_TAB,
+};
+use std::time::Instant;
 
 pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    let chunks = Layout::default()
-        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
-        .split(f.size());
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(
-            Style::default()
-                .fg(Color::Rgb(100, 96, 107))
-                .bg(Color::Rgb(41, 43, 47)),
-        )
-        .title("Greetings")
-        .title_style(
-            Style::default()
-                .fg(Color::Rgb(203, 193, 213))
-                .modifier(Modifier::BOLD)
-                .bg(Color::Rgb(41, 43, 47)),
-        );
-    let text = [
-        Text::raw("Welcome to "),
-        Text::styled(
-            "Magni",
-            Style::default()
-                .fg(Color::Rgb(109, 80, 168))
-                .bg(Color::Rgb(41, 43, 47))
-                .modifier(Modifier::BOLD),
+    let theme = app.theme;
+    let banners: [(&str, Vec<String>); 4] = [
+        (
+            "Reboot required",
+            app.reboot_reasons
+                .iter()
+                .map(|reason| reason.to_string())
+                .collect(),
+        ),
+        (
+            "RAID degraded",
+            app.md_arrays
+                .iter()
+                .filter(|array| array.is_degraded())
+                .map(|array| array.to_string())
+                .collect(),
         ),
-        Text::styled(
-            "Linux",
-            Style::default()
-                .fg(Color::Rgb(255, 255, 255))
-                .bg(Color::Rgb(41, 43, 47))
-                .modifier(Modifier::BOLD),
+        (
+            "Disks",
+            app.disks
+                .iter()
+                .flat_map(|disk| disk.warnings(&app.disk_thresholds))
+                .collect(),
+        ),
+        (
+            "Processes",
+            app.process_states.warnings(&app.blocked, Instant::now()),
         ),
     ];
+    let banners: Vec<&(&str, Vec<String>)> = banners
+        .iter()
+        .filter(|(_, lines)| !lines.is_empty())
+        .collect();
+    let greeting = render(&app.greeting.template, &app.greeting_facts);
+    let greeting_lines = greeting
+        .iter()
+        .map(|(_, value)| value.matches('\n').count())
+        .sum::<usize>()
+        + 1;
+    let logo_height = app.logo.as_ref().map_or(0, ShownLogo::height);
+    let header_height = greeting_lines.max(logo_height) as u16 + 2;
+    let mut constraints = vec![Constraint::Length(header_height)];
+    for (_, lines) in &banners {
+        constraints.push(Constraint::Length(lines.len() as u16 + 2));
+    }
+    constraints.push(Constraint::Min(1));
+    let chunks = Layout::default().constraints(constraints).split(f.size());
+    let greeting_title = if app.virt.is_bare_metal() {
+        "Greetings".to_string()
+    } else {
+        format!("Greetings ({})", app.virt)
+    };
+    let block = theme.block(&greeting_title);
+    let text: Vec<Text> = greeting
+        .into_iter()
+        .map(|(field, value)| {
+            Text::styled(
+                value,
+                degrade_style(theme.greeting(&app.greeting, field), app.color_depth),
+            )
+        })
+        .collect();
     let paragraph = Paragraph::new(text.iter())
         .block(block)
-        .style(Style::default().bg(Color::Rgb(41, 43, 47)))
+        .style(theme.base())
         .wrap(true);
-    f.render_widget(paragraph, chunks[0]);
-    draw_first_tab(f, app, chunks[1]);
+    let logo_width = app.logo.as_ref().map_or(0, |logo| logo.width() as u16 + 4);
+    let header = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(logo_width), Constraint::Min(1)].as_ref())
+        .split(chunks[0]);
+    app.logo_area = None;
+    match app.logo {
+        Some(ShownLogo::Ascii(logo)) => draw_logo(f, &theme, app.color_depth, logo, header[0]),
+        Some(ShownLogo::Image(_)) => {
+            // Left blank, main paints the image over it once tui is done.
+            draw_logo(f, &theme, app.color_depth, &Logo::BLANK, header[0]);
+            app.logo_area = Some(header[0]);
+        }
+        None => {}
+    }
+    let header = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(header[1]);
+    f.render_widget(paragraph, header[0]);
+    draw_host(f, app, header[1]);
+    for (i, (title, lines)) in banners.iter().enumerate() {
+        draw_banner(f, &theme, title, lines, chunks[i + 1]);
+    }
+    let body = Layout::default()
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(chunks[banners.len() + 1]);
+    let tabs = Tabs::default()
+        .titles(&app.tabs.titles)
+        .select(app.tabs.index)
+        .style(theme.text())
+        .highlight_style(theme.selected());
+    let tab_bar = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Length(24)].as_ref())
+        .split(body[0]);
+    f.render_widget(tabs, tab_bar[0]);
+    let keys = [Text::raw("Tab or \u{2190}/\u{2192}: switch")];
+    let keys = Paragraph::new(keys.iter())
+        .style(theme.muted())
+        .alignment(Alignment::Right);
+    f.render_widget(keys, tab_bar[1]);
+    match app.tabs.index {
+        PROCESSES_TAB => draw_processes(f, app, body[1]),
+        _ => draw_first_tab(f, app, body[1]),
+    }
+}
+
+/// The distro logo in its ANSI colours.
+fn draw_logo<B>(f: &mut Frame<B>, theme: &Theme, depth: ColorDepth, logo: &Logo, area: Rect)
+where
+    B: Backend,
+{
+    let mut text = Vec::new();
+    for runs in logo.lines() {
+        text.push(Text::raw(" "));
+        for (ansi, run) in runs {
+            text.push(Text::styled(
+                run,
+                theme
+                    .base()
+                    .fg(degrade(Color::Indexed(ansi), depth))
+                    .modifier(Modifier::BOLD),
+            ));
+        }
+        text.push(Text::raw("\n"));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border());
+    let paragraph = Paragraph::new(text.iter())
+        .block(block)
+        .style(theme.base())
+        .wrap(false);
+    f.render_widget(paragraph, area);
+}
+
+/// Hardware model, firmware and, when enabled, the serial number.
+fn draw_host<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let host = &app.host;
+    let details: Vec<String> = vec![
+        host.description(),
+        host.firmware(),
+        host.serial.as_ref().map(|serial| format!("S/N {}", serial)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let details = if details.is_empty() {
+        "Unknown".to_string()
+    } else {
+        details.join(" · ")
+    };
+    let text = [Text::styled(details, theme.text())];
+    let block = theme.block("Host");
+    let paragraph = Paragraph::new(text.iter())
+        .block(block)
+        .style(theme.base())
+        .wrap(false);
+    f.render_widget(paragraph, area);
+}
+
+/// A loud red box for things that need the admin's attention right away.
+fn draw_banner<B>(f: &mut Frame<B>, theme: &Theme, title: &str, lines: &[String], area: Rect)
+where
+    B: Backend,
+{
+    let style = theme.banner();
+    let text: Vec<Text> = lines
+        .iter()
+        .map(|line| Text::styled(format!("{}\n", line), style))
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+        .title_style(style);
+    let paragraph = Paragraph::new(text.iter())
+        .block(block)
+        .style(style)
+        .wrap(true);
+    f.render_widget(paragraph, area);
 }
 
 fn draw_first_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
@@ -63,10 +221,26 @@ where
     B: Backend,
 {
     let docker = get_docker_processes();
+    let storage_rows =
+        (app.md_arrays.len() + app.zfs_pools.len() + app.btrfs.len() + app.smart.health.len())
+            as u16;
+    let storage_height = if storage_rows == 0 {
+        0
+    } else {
+        storage_rows + 2
+    };
+    let battery_height = if app.power.batteries.is_empty() {
+        0
+    } else {
+        app.power.batteries.len() as u16 * 2 + 2
+    };
     let chunks = Layout::default()
         .constraints(
             [
-                Constraint::Length((get_all_disks().len() * 2) as u16 + 2),
+                Constraint::Length((app.disks.len() * 2) as u16 + 2),
+                Constraint::Length(storage_height),
+                Constraint::Length(battery_height),
+                Constraint::Length(6),
                 Constraint::Min(7),
                 Constraint::Length(7),
             ]
@@ -74,84 +248,37 @@ where
         )
         .split(area);
     draw_gauges(f, app, chunks[0]);
-    draw_charts(f, app, chunks[1], docker);
-    draw_text(f, chunks[2]);
+    if storage_rows > 0 {
+        draw_storage_health(f, app, chunks[1]);
+    }
+    if battery_height > 0 {
+        draw_batteries(f, app, chunks[2]);
+    }
+    draw_memory(f, app, chunks[3]);
+    draw_charts(f, app, chunks[4], docker);
+    draw_text(f, &app.theme, chunks[5]);
 }
 
 fn draw_gauges<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
 where
     B: Backend,
 {
-    let disks = get_all_disks();
+    let theme = app.theme;
+    let disks = &app.disks;
     let mut c: usize = 0;
-    let chunks = match get_all_disks().len() {
-        2 => Layout::default()
-            .constraints([Constraint::Length(2), Constraint::Length(2)].as_ref())
-            .margin(1)
-            .split(area),
-        3 => Layout::default()
-            .constraints(
-                [
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                ]
-                .as_ref(),
-            )
-            .margin(1)
-            .split(area),
-        4 => Layout::default()
-            .constraints(
-                [
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                ]
-                .as_ref(),
-            )
-            .margin(1)
-            .split(area),
-        5 => Layout::default()
-            .constraints(
-                [
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                    Constraint::Length(2),
-                ]
-                .as_ref(),
-            )
-            .margin(1)
-            .split(area),
-        _ => Layout::default()
-            .constraints([Constraint::Length(2)].as_ref())
-            .margin(1)
-            .split(area),
-    };
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(
-            Style::default()
-                .fg(Color::Rgb(100, 96, 107))
-                .bg(Color::Rgb(41, 43, 47)),
-        )
-        .title("Drives")
-        .title_style(
-            Style::default()
-                .fg(Color::Rgb(203, 193, 213))
-                .modifier(Modifier::BOLD)
-                .bg(Color::Rgb(41, 43, 47)),
-        )
-        .style(Style::default().bg(Color::Rgb(41, 43, 47)));
+    let chunks = Layout::default()
+        .constraints(vec![Constraint::Length(2); disks.len()])
+        .margin(1)
+        .split(area);
+    let block = theme.block("Drives").style(theme.base());
     f.render_widget(block, area);
     for disk in disks {
-        let disk_perc =
-            (((disk.total_space - disk.available_space) as f64 / disk.total_space as f64) * 100.0)
-                .ceil();
-        let gauge_title = String::from("Mount Path: ") + disk.mount_point.to_str().unwrap();
-        let label = match disk.total_space {
+        let disk_perc = disk.used_percent().ceil();
+        let mut gauge_title = String::from("Mount Path: ") + disk.mount_point.to_str().unwrap();
+        if disk.read_only {
+            gauge_title += " (read-only)";
+        }
+        let mut label = match disk.total_space {
             1_000..=999_999 => {
                 disk_perc.to_string()
                     + "% out of "
@@ -183,22 +310,216 @@ where
                     + &format!("{}", MemUnit::B(disk.total_space as f64))
             }
         };
+        if let Some(inodes) = disk.inodes_used_percent() {
+            label += &format!(", {:.0}% of inodes", inodes.ceil());
+        }
+        if disk.reserved_space() > 0 {
+            label += &format!(", {} reserved", MemUnit::from_bytes(disk.reserved_space()));
+        }
+        let gauge_fg = if disk.warnings(&app.disk_thresholds).is_empty() {
+            theme.accent
+        } else {
+            theme.critical
+        };
         //let label = format!("{}", disk_perc);
         let gauge = Gauge::default()
             .block(Block::default().title(&gauge_title))
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(93, 77, 122))
-                    .bg(Color::Rgb(33, 32, 38))
-                    .modifier(Modifier::ITALIC | Modifier::BOLD),
-            )
+            .style(theme.gauge(gauge_fg))
             .label(&label)
             .percent(disk_perc as u16);
-        f.render_widget(gauge, chunks[c]);
+        let row = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+            .split(chunks[c]);
+        f.render_widget(gauge, row[0]);
+        if let Some((io, history)) = app.disk_io.for_disk(disk) {
+            let await_ms = match io.await_ms {
+                Some(await_ms) => format!("{:.1}ms", await_ms),
+                None => "idle".to_string(),
+            };
+            let rates = format!(
+                "R {}/s W {}/s {:.0} IOPS {}",
+                MemUnit::from_bytes(io.read_bytes_per_sec as u64),
+                MemUnit::from_bytes(io.write_bytes_per_sec as u64),
+                io.iops,
+                await_ms
+            );
+            let sparkline = Sparkline::default()
+                .block(Block::default().title(&rates))
+                .style(theme.base().fg(theme.accent))
+                .data(history);
+            f.render_widget(sparkline, row[1]);
+        }
         c += 1;
     }
 }
 
+/// RAID arrays, ZFS pools, btrfs file systems and SMART health, in red when they need
+/// attention.
+fn draw_storage_health<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let healthy_style = theme.text();
+    let unhealthy_style = theme.critical();
+    let arrays = app
+        .md_arrays
+        .iter()
+        .map(|array| (array.to_string(), array.active && !array.is_degraded()));
+    let pools = app
+        .zfs_pools
+        .iter()
+        .map(|pool| (pool.to_string(), pool.is_healthy()));
+    let filesystems = app
+        .btrfs
+        .iter()
+        .map(|fs| (fs.to_string(), fs.error_count() == 0));
+    let disks = app
+        .smart
+        .health
+        .iter()
+        .map(|disk| (disk.to_string(), disk.is_healthy()));
+    let text: Vec<Text> = arrays
+        .chain(pools)
+        .chain(filesystems)
+        .chain(disks)
+        .map(|(line, healthy)| {
+            let style = if healthy {
+                healthy_style
+            } else {
+                unhealthy_style
+            };
+            Text::styled(format!("{}\n", line), style)
+        })
+        .collect();
+    let block = theme.block("Storage health");
+    let paragraph = Paragraph::new(text.iter())
+        .block(block)
+        .style(theme.base())
+        .wrap(false);
+    f.render_widget(paragraph, area);
+}
+
+fn draw_batteries<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let title = match app.power.ac_online {
+        Some(true) => "Battery (on AC)",
+        Some(false) => "Battery (on battery)",
+        None => "Battery",
+    };
+    let block = theme.block(title).style(theme.base());
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .constraints(vec![Constraint::Length(2); app.power.batteries.len()])
+        .margin(1)
+        .split(area);
+    for (battery, chunk) in app.power.batteries.iter().zip(chunks) {
+        let capacity = battery.capacity.unwrap_or(0).min(100);
+        let fg = if capacity <= 10 && battery.status == "Discharging" {
+            theme.critical
+        } else {
+            theme.accent
+        };
+        let label = battery.to_string();
+        let gauge = Gauge::default()
+            .block(Block::default().title(&battery.name))
+            .style(theme.gauge(fg))
+            .label(&label)
+            .percent(capacity as u16);
+        f.render_widget(gauge, chunk);
+    }
+}
+
+fn draw_memory<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let mem = &app.memory;
+    let block = theme.block("Memory").style(theme.base());
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .constraints([Constraint::Length(2), Constraint::Min(1)].as_ref())
+        .margin(1)
+        .split(area);
+    let gauge_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(if mem.swap_total > 0 {
+            vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+        } else {
+            vec![Constraint::Percentage(100)]
+        })
+        .split(chunks[0]);
+    let kib = |kib: u64| format!("{}", MemUnit::MiB(kib as f64 * 1024_f64));
+    let gauge_style = theme.gauge(theme.accent);
+    let ram_label = format!(
+        "{:.0}% ({} of {})",
+        mem.used_percent(),
+        kib(mem.used()),
+        kib(mem.total)
+    );
+    let gauge = Gauge::default()
+        .block(Block::default().title("RAM used"))
+        .style(gauge_style)
+        .label(&ram_label)
+        .percent(mem.used_percent().min(100.0) as u16);
+    f.render_widget(gauge, gauge_chunks[0]);
+    if mem.swap_total > 0 {
+        let swap_label = format!(
+            "{:.0}% ({} of {})",
+            mem.swap_used_percent(),
+            kib(mem.swap_used()),
+            kib(mem.swap_total)
+        );
+        let gauge = Gauge::default()
+            .block(Block::default().title("Swap used"))
+            .style(gauge_style)
+            .label(&swap_label)
+            .percent(mem.swap_used_percent().min(100.0) as u16);
+        f.render_widget(gauge, gauge_chunks[1]);
+    }
+
+    let mut breakdown = format!(
+        "Available {}  Shared {}  Slab {}  Dirty {}",
+        kib(mem.available),
+        kib(mem.shared),
+        kib(mem.slab),
+        kib(mem.dirty)
+    );
+    if mem.hugepages_total > 0 {
+        breakdown += &format!(
+            "  Hugepages {}/{} free ({} each)",
+            mem.hugepages_free,
+            mem.hugepages_total,
+            kib(mem.hugepage_size)
+        );
+    }
+    breakdown += "\n";
+    for zram in &app.zram {
+        breakdown += &format!("{} {:.1}x  ", zram.name, zram.ratio());
+    }
+    if mem.zswap > 0 {
+        breakdown += &format!("zswap {} holding {}  ", kib(mem.zswap), kib(mem.zswapped));
+    }
+    let stall = |avg10: Option<f32>| match avg10 {
+        Some(avg10) => format!("{:.1}%", avg10),
+        None => "n/a".to_string(),
+    };
+    breakdown += &format!(
+        "Pressure cpu {} mem {} io {}",
+        stall(app.pressure.cpu),
+        stall(app.pressure.memory),
+        stall(app.pressure.io)
+    );
+    let text = [Text::styled(breakdown, theme.text())];
+    let paragraph = Paragraph::new(text.iter()).style(theme.base()).wrap(true);
+    f.render_widget(paragraph, chunks[1]);
+}
+
 fn draw_charts<B>(
     f: &mut Frame<B>,
     app: &mut App,
@@ -207,6 +528,7 @@ fn draw_charts<B>(
 ) where
     B: Backend,
 {
+    let theme = app.theme;
     let constraints = if app.show_chart {
         vec![Constraint::Percentage(50), Constraint::Percentage(50)]
     } else {
@@ -227,23 +549,11 @@ fn draw_charts<B>(
                 .split(chunks[0]);
 
             // Colors
-            let info_style = Style::default()
-                .fg(Color::Rgb(178, 178, 178))
-                .bg(Color::Rgb(41, 43, 47));
-            let warning_style = Style::default()
-                .fg(Color::Yellow)
-                .bg(Color::Rgb(41, 43, 47));
-            let error_style = Style::default()
-                .fg(Color::Rgb(93, 77, 122))
-                .bg(Color::Rgb(41, 43, 47));
-            let critical_style = Style::default()
-                .fg(Color::Red)
-                .modifier(Modifier::BOLD)
-                .bg(Color::Rgb(41, 43, 47));
-            let success_style = Style::default()
-                .fg(Color::Green)
-                .modifier(Modifier::BOLD)
-                .bg(Color::Rgb(41, 43, 47));
+            let info_style = theme.text();
+            let warning_style = theme.warn();
+            let error_style = theme.base().fg(theme.critical);
+            let critical_style = theme.critical();
+            let success_style = theme.ok();
 
             // List Systemd units
             let units = app
@@ -264,33 +574,35 @@ fn draw_charts<B>(
                 });
 
             let units = List::new(units)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_style(
-                            Style::default()
-                                .fg(Color::Rgb(100, 96, 107))
-                                .bg(Color::Rgb(41, 43, 47)),
-                        )
-                        .title("Systemd")
-                        .title_style(
-                            Style::default()
-                                .fg(Color::Rgb(203, 193, 213))
-                                .modifier(Modifier::BOLD)
-                                .bg(Color::Rgb(41, 43, 47)),
-                        ),
+                .block(theme.block("Systemd"))
+                .style(theme.base());
+            let logins_height = 4 + app
+                .logins
+                .failed
+                .as_ref()
+                .map_or(0, |failed| failed.top_sources.len() as u16);
+            let systemd_chunks = Layout::default()
+                .constraints(
+                    [
+                        Constraint::Min(3),
+                        Constraint::Length(logins_height),
+                        Constraint::Length(3),
+                    ]
+                    .as_ref(),
                 )
-                .style(Style::default().bg(Color::Rgb(41, 43, 47)));
-            f.render_stateful_widget(units, chunks[0], &mut app.tasks.state);
+                .split(chunks[0]);
+            f.render_stateful_widget(units, systemd_chunks[0], &mut app.tasks.state);
+            draw_logins(f, app, systemd_chunks[1]);
+            draw_updates(f, app, systemd_chunks[2]);
 
             // List Docker items
             match docker {
                 Some(x) => {
-                    let up_style = Style::default().fg(Color::Green).bg(Color::Rgb(41, 43, 47));
-                    let failure_style = Style::default()
-                        .fg(Color::Red)
-                        .modifier(Modifier::RAPID_BLINK | Modifier::CROSSED_OUT)
-                        .bg(Color::Rgb(41, 43, 47));
+                    let up_style = theme.base().fg(theme.ok);
+                    let failure_style = theme
+                        .base()
+                        .fg(theme.critical)
+                        .modifier(Modifier::RAPID_BLINK | Modifier::CROSSED_OUT);
                     let header = ["Image", "Status"];
                     let rows = x.iter().map(|(i, s)| {
                         let style = if s.contains("Up") {
@@ -301,28 +613,9 @@ fn draw_charts<B>(
                         Row::StyledData(vec![i, s].into_iter(), style)
                     });
                     let table = Table::new(header.iter(), rows)
-                        .block(
-                            Block::default()
-                                .title("Docker")
-                                .borders(Borders::ALL)
-                                .border_style(
-                                    Style::default()
-                                        .fg(Color::Rgb(100, 96, 107))
-                                        .bg(Color::Rgb(41, 43, 47)),
-                                )
-                                .title_style(
-                                    Style::default()
-                                        .fg(Color::Rgb(203, 193, 213))
-                                        .modifier(Modifier::BOLD)
-                                        .bg(Color::Rgb(41, 43, 47)),
-                                ),
-                        )
-                        .style(Style::default().bg(Color::Rgb(41, 43, 47)))
-                        .header_style(
-                            Style::default()
-                                .fg(Color::Rgb(178, 178, 178))
-                                .bg(Color::Rgb(41, 43, 47)),
-                        )
+                        .block(theme.block("Docker"))
+                        .style(theme.base())
+                        .header_style(theme.text())
                         .widths(&[
                             Constraint::Length(15),
                             Constraint::Length(15),
@@ -332,18 +625,12 @@ fn draw_charts<B>(
                 }
                 None => {
                     let text = [Text::raw(" ")];
-                    let block = Block::default()
-                        .borders(Borders::ALL)
-                        .border_style(
-                            Style::default()
-                                .fg(Color::Rgb(100, 96, 107))
-                                .bg(Color::Rgb(41, 43, 47)),
-                        )
-                        .title("Empty")
-                        .title_style(Style::default().fg(Color::Gray).modifier(Modifier::BOLD));
+                    let block = theme
+                        .block("Empty")
+                        .title_style(theme.muted().modifier(Modifier::BOLD));
                     let paragraph = Paragraph::new(text.iter())
                         .block(block)
-                        .style(Style::default().bg(Color::Rgb(41, 43, 47)))
+                        .style(theme.base())
                         .wrap(true);
                     f.render_widget(paragraph, chunks[1]);
                 }
@@ -351,29 +638,19 @@ fn draw_charts<B>(
         }
 
         // Create Bar graph with
-        // Memory Usage
-        // Swap Usage
+        // RAM used
+        // Swap used
         // Load Avg
         // Proc Root / Proc User
         // Network Connections per address
+        let bars: Vec<(&str, u64)> = app
+            .barchart
+            .iter()
+            .map(|(label, value)| (label.as_str(), *value))
+            .collect();
         let barchart = BarChart::default()
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Stats")
-                    .border_style(
-                        Style::default()
-                            .fg(Color::Rgb(100, 96, 107))
-                            .bg(Color::Rgb(41, 43, 47)),
-                    )
-                    .title_style(
-                        Style::default()
-                            .fg(Color::Rgb(203, 193, 213))
-                            .modifier(Modifier::BOLD)
-                            .bg(Color::Rgb(41, 43, 47)),
-                    ),
-            )
-            .data(&app.barchart)
+            .block(theme.block("Stats"))
+            .data(&bars)
             .bar_width(9)
             .bar_gap(1)
             .bar_set(if app.enhanced_graphics {
@@ -383,23 +660,27 @@ fn draw_charts<B>(
             })
             .value_style(
                 Style::default()
-                    .fg(Color::Rgb(33, 32, 38))
-                    .bg(Color::Rgb(93, 77, 122))
+                    .fg(theme.gauge_background)
+                    .bg(theme.accent)
                     .modifier(Modifier::ITALIC),
             )
-            .label_style(
-                Style::default()
-                    .fg(Color::Rgb(178, 178, 178))
-                    .bg(Color::Rgb(41, 43, 47)),
-            )
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(93, 77, 122))
-                    .bg(Color::Rgb(41, 43, 47)),
-            );
+            .label_style(theme.text())
+            .style(theme.base().fg(theme.accent));
         f.render_widget(barchart, chunks[1]);
     }
     if app.show_chart {
+        let chunks = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length(app.sessions.len() as u16 + 3),
+                    Constraint::Percentage(50),
+                    Constraint::Percentage(50),
+                ]
+                .as_ref(),
+            )
+            .split(chunks[1]);
+        draw_sessions(f, app, chunks[0]);
+        draw_listening_ports(f, app, chunks[1]);
         let x_labels = [
             format!("{}", app.signals.window[0]),
             format!("{}", (app.signals.window[0] + app.signals.window[1]) / 2.0),
@@ -409,7 +690,7 @@ fn draw_charts<B>(
             Dataset::default()
                 .name("data2")
                 .marker(symbols::Marker::Dot)
-                .style(Style::default().fg(Color::Cyan).bg(Color::Rgb(41, 43, 47)))
+                .style(theme.base().fg(theme.highlight))
                 .data(&app.signals.sin1.points),
             Dataset::default()
                 .name("data3")
@@ -418,97 +699,286 @@ fn draw_charts<B>(
                 } else {
                     symbols::Marker::Dot
                 })
-                .style(
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .bg(Color::Rgb(41, 43, 47)),
-                )
+                .style(theme.warn())
                 .data(&app.signals.sin2.points),
         ];
         let chart = Chart::default()
             .block(
-                Block::default()
-                    .title("Chart")
-                    .title_style(
-                        Style::default()
-                            .fg(Color::Gray)
-                            .modifier(Modifier::BOLD)
-                            .bg(Color::Rgb(41, 43, 47)),
-                    )
-                    .borders(Borders::ALL)
-                    .border_style(
-                        Style::default()
-                            .fg(Color::Rgb(100, 96, 107))
-                            .bg(Color::Rgb(41, 43, 47)),
-                    )
-                    .style(Style::default().bg(Color::Rgb(41, 43, 47))),
+                theme
+                    .block("Chart")
+                    .title_style(theme.muted().modifier(Modifier::BOLD))
+                    .style(theme.base()),
             )
             .x_axis(
                 Axis::default()
                     .title("X Axis")
-                    .title_style(Style::default().bg(Color::Rgb(41, 43, 47)))
-                    .style(Style::default().fg(Color::Gray).bg(Color::Rgb(41, 43, 47)))
-                    .labels_style(
-                        Style::default()
-                            .modifier(Modifier::ITALIC)
-                            .bg(Color::Rgb(41, 43, 47)),
-                    )
+                    .title_style(theme.base())
+                    .style(theme.muted())
+                    .labels_style(theme.base().modifier(Modifier::ITALIC))
                     .bounds(app.signals.window)
                     .labels(&x_labels),
             )
             .y_axis(
                 Axis::default()
                     .title("Y Axis")
-                    .title_style(Style::default().bg(Color::Rgb(41, 43, 47)))
-                    .style(Style::default().fg(Color::Gray).bg(Color::Rgb(41, 43, 47)))
-                    .labels_style(Style::default().modifier(Modifier::ITALIC).bg(Color::Rgb(41, 43, 47)))
+                    .title_style(theme.base())
+                    .style(theme.muted())
+                    .labels_style(theme.base().modifier(Modifier::ITALIC))
                     .bounds([-20.0, 20.0])
                     .labels(&["-20", "0", "20"]),
             )
             .datasets(&datasets)
-            .style(Style::default().bg(Color::Rgb(41, 43, 47)));
-        f.render_widget(chart, chunks[1]);
+            .style(theme.base());
+        f.render_widget(chart, chunks[2]);
+    }
+}
+
+fn draw_logins<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let info_style = theme.text();
+    let critical_style = theme.critical();
+    let success_style = theme.ok();
+
+    let mut text = vec![Text::styled("Last login: ", info_style)];
+    match &app.logins.last_login {
+        Some(last) => text.push(Text::styled(
+            format!(
+                "{} from {}\n",
+                format_timestamp(last.time),
+                if last.host.is_empty() {
+                    &last.tty
+                } else {
+                    &last.host
+                }
+            ),
+            info_style,
+        )),
+        None => text.push(Text::styled("never\n", info_style)),
+    }
+    text.push(Text::styled("Failed: ", info_style));
+    match &app.logins.failed {
+        Some(failed) => {
+            text.push(Text::styled(
+                format!("{} since last login\n", failed.count),
+                if failed.count > 0 {
+                    critical_style
+                } else {
+                    success_style
+                },
+            ));
+            for (source, count) in &failed.top_sources {
+                text.push(Text::styled(
+                    format!("  {} ({})\n", source, count),
+                    info_style,
+                ));
+            }
+        }
+        None => text.push(Text::styled("btmp not readable\n", info_style)),
     }
+    let block = theme.block("Logins");
+    let paragraph = Paragraph::new(text.iter())
+        .block(block)
+        .style(theme.base())
+        .wrap(true);
+    f.render_widget(paragraph, area);
 }
 
-fn draw_text<B>(f: &mut Frame<B>, area: Rect)
+fn draw_updates<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let text = match &app.updates {
+        Some(updates) => {
+            let style = if updates.security.unwrap_or(0) > 0 {
+                theme.critical()
+            } else if updates.pending > 0 {
+                theme.warn()
+            } else {
+                theme.ok()
+            };
+            let mut summary = format!("{} pending", updates.pending);
+            if let Some(security) = updates.security {
+                summary += &format!(", {} security", security);
+            }
+            [Text::styled(summary, style)]
+        }
+        None => [Text::styled("Unknown", theme.text())],
+    };
+    let title = match &app.updates {
+        Some(updates) => format!("Updates ({})", updates.manager),
+        None => "Updates".to_string(),
+    };
+    let block = theme.block(&title);
+    let paragraph = Paragraph::new(text.iter())
+        .block(block)
+        .style(theme.base())
+        .wrap(true);
+    f.render_widget(paragraph, area);
+}
+
+fn draw_sessions<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let row_style = theme.text();
+    let current_style = theme.selected();
+    let header = ["User", "TTY", "From", "Login", "Idle"];
+    let rows = app.sessions.iter().map(|session| {
+        Row::StyledData(
+            vec![
+                session.user.clone(),
+                session.tty.clone(),
+                if session.host.is_empty() {
+                    "-".to_string()
+                } else {
+                    session.host.clone()
+                },
+                format_timestamp(session.login_time),
+                session
+                    .idle
+                    .map(format_idle)
+                    .unwrap_or_else(|| "?".to_string()),
+            ]
+            .into_iter(),
+            if session.current {
+                current_style
+            } else {
+                row_style
+            },
+        )
+    });
+    let table = Table::new(header.iter(), rows)
+        .block(theme.block("Sessions"))
+        .style(theme.base())
+        .header_style(theme.text().modifier(Modifier::BOLD))
+        .widths(&[
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(16),
+            Constraint::Length(12),
+            Constraint::Length(5),
+        ]);
+    f.render_widget(table, area);
+}
+
+/// Every process, sorted with `c` (CPU), `m` (memory) or `p` (PID) and scrolled with the arrows.
+fn draw_processes<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let row_style = theme.text();
+    let header = ["PID", "User", "CPU%", "Memory", "Time", "Command"];
+    let rows = app.processes.processes.iter().map(|process| {
+        Row::StyledData(
+            vec![
+                process.pid.to_string(),
+                process.user.clone(),
+                format!("{:.1}", process.cpu_percent),
+                MemUnit::from_bytes(process.rss).to_string(),
+                format_idle(process.runtime),
+                process.command.clone(),
+            ]
+            .into_iter(),
+            row_style,
+        )
+    });
+    let title = format!(
+        "Processes by {} (c: CPU, m: memory, p: PID)",
+        match app.process_sort {
+            ProcessSort::Cpu => "CPU",
+            ProcessSort::Memory => "memory",
+            ProcessSort::Pid => "PID",
+        }
+    );
+    let states = &app.process_states;
+    let users: Vec<String> = states
+        .by_user
+        .iter()
+        .take(5)
+        .map(|(user, count)| format!("{} {}", user, count))
+        .collect();
+    let summary = format!(
+        "{} processes, {} threads; {} zombie, {} blocked, {} stopped; {}",
+        states.all,
+        states.threads,
+        states.zombies.len(),
+        states.blocked.len(),
+        states.stopped,
+        users.join(", ")
+    );
+    let chunks = Layout::default()
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(area);
+    let text = [Text::raw(summary)];
+    f.render_widget(Paragraph::new(text.iter()).style(row_style), chunks[0]);
+    let table = Table::new(header.iter(), rows)
+        .block(theme.block(&title))
+        .style(theme.base())
+        .header_style(theme.text().modifier(Modifier::BOLD))
+        .highlight_style(theme.selected())
+        .widths(&[
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Min(10),
+        ]);
+    f.render_stateful_widget(table, chunks[1], &mut app.process_table);
+}
+
+fn draw_listening_ports<B>(f: &mut Frame<B>, app: &App, area: Rect)
+where
+    B: Backend,
+{
+    let theme = app.theme;
+    let row_style = theme.text();
+    let header = ["Proto", "Address", "Process"];
+    let rows = app.listening_ports.iter().map(|port| {
+        Row::StyledData(
+            vec![
+                port.protocol.to_string(),
+                port.address.to_string(),
+                port.process.clone().unwrap_or_else(|| "-".to_string()),
+            ]
+            .into_iter(),
+            row_style,
+        )
+    });
+    let table = Table::new(header.iter(), rows)
+        .block(theme.block("Listening"))
+        .style(theme.base())
+        .header_style(theme.text().modifier(Modifier::BOLD))
+        .widths(&[
+            Constraint::Length(5),
+            Constraint::Length(24),
+            Constraint::Min(10),
+        ]);
+    f.render_widget(table, area);
+}
+
+fn draw_text<B>(f: &mut Frame<B>, theme: &Theme, area: Rect)
 where
     B: Backend,
 {
     let quote = get_quote();
     let text = [
-        Text::styled(
-            quote.quote,
-            Style::default()
-                .fg(Color::Rgb(178, 178, 178))
-                .bg(Color::Rgb(41, 43, 47)),
-        ),
-        Text::styled("\n\n -  ", Style::default().bg(Color::Rgb(41, 43, 47))),
-        Text::styled(
-            quote.author,
-            Style::default()
-                .fg(Color::Rgb(104, 104, 104))
-                .modifier(Modifier::BOLD)
-                .bg(Color::Rgb(41, 43, 47)),
-        ),
+        Text::styled(quote.quote, theme.text()),
+        Text::styled("\n\n -  ", theme.base()),
+        Text::styled(quote.author, theme.muted().modifier(Modifier::BOLD)),
     ];
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(
-            Style::default()
-                .fg(Color::Rgb(100, 96, 107))
-                .bg(Color::Rgb(41, 43, 47)),
-        )
-        .title("Quote")
-        .title_style(
-            Style::default()
-                .fg(Color::Gray)
-                .modifier(Modifier::BOLD)
-                .bg(Color::Rgb(41, 43, 47)),
-        );
+    let block = theme
+        .block("Quote")
+        .title_style(theme.muted().modifier(Modifier::BOLD));
     let paragraph = Paragraph::new(text.iter())
         .block(block)
-        .style(Style::default().bg(Color::Rgb(41, 43, 47)))
+        .style(theme.base())
         .wrap(true);
     f.render_widget(paragraph, area);
 }
@@ -1,3 +1,5 @@
 mod app;
 pub mod layout;
-pub use app::App;
+pub mod theme;
+pub use app::{App, PROCESSES_TAB};
+pub use theme::Theme;
@@ -0,0 +1,405 @@
+// Colours of the TUI. Widgets take their styles from the helpers here, so a theme only
+// has to name its colours once.
+
+use crate::colors::{is_close, ColorDepth};
+use crate::sys::{
+    greeting::{Field, Greeting},
+    AnsiColor, OsRelease,
+};
+use tui::{
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders},
+};
+
+/// Names `Theme::preset` knows, the first being the default.
+pub const PRESETS: [&str; 5] = ["dark", "light", "solarized", "nord", "16color"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    /// The unfilled part of gauges.
+    pub gauge_background: Color,
+    pub border: Color,
+    pub title: Color,
+    pub text: Color,
+    /// Secondary text, like chart axes and the quote's author.
+    pub muted: Color,
+    /// Gauges, bars and the selected tab or row.
+    pub accent: Color,
+    /// Text that should stand out, like the user's name in the greeting.
+    pub highlight: Color,
+    pub ok: Color,
+    pub warn: Color,
+    pub critical: Color,
+    /// Text on the `critical` background of banners.
+    pub banner_text: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            background: Color::Rgb(41, 43, 47),
+            gauge_background: Color::Rgb(33, 32, 38),
+            border: Color::Rgb(100, 96, 107),
+            title: Color::Rgb(203, 193, 213),
+            text: Color::Rgb(178, 178, 178),
+            muted: Color::Gray,
+            accent: Color::Rgb(93, 77, 122),
+            highlight: Color::Rgb(109, 80, 168),
+            ok: Color::Green,
+            warn: Color::Yellow,
+            critical: Color::Red,
+            banner_text: Color::White,
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            background: Color::Rgb(250, 250, 250),
+            gauge_background: Color::Rgb(225, 225, 230),
+            border: Color::Rgb(160, 160, 170),
+            title: Color::Rgb(40, 40, 50),
+            text: Color::Rgb(60, 60, 60),
+            muted: Color::Rgb(130, 130, 130),
+            accent: Color::Rgb(120, 90, 180),
+            highlight: Color::Rgb(90, 60, 150),
+            ok: Color::Rgb(40, 140, 60),
+            warn: Color::Rgb(190, 130, 0),
+            critical: Color::Rgb(200, 40, 40),
+            banner_text: Color::Rgb(250, 250, 250),
+        }
+    }
+
+    /// Ethan Schoonover's Solarized, the dark variant.
+    pub fn solarized() -> Theme {
+        Theme {
+            background: Color::Rgb(0, 43, 54),
+            gauge_background: Color::Rgb(7, 54, 66),
+            border: Color::Rgb(88, 110, 117),
+            title: Color::Rgb(147, 161, 161),
+            text: Color::Rgb(131, 148, 150),
+            muted: Color::Rgb(88, 110, 117),
+            accent: Color::Rgb(38, 139, 210),
+            highlight: Color::Rgb(108, 113, 196),
+            ok: Color::Rgb(133, 153, 0),
+            warn: Color::Rgb(181, 137, 0),
+            critical: Color::Rgb(220, 50, 47),
+            banner_text: Color::Rgb(253, 246, 227),
+        }
+    }
+
+    pub fn nord() -> Theme {
+        Theme {
+            background: Color::Rgb(46, 52, 64),
+            gauge_background: Color::Rgb(59, 66, 82),
+            border: Color::Rgb(76, 86, 106),
+            title: Color::Rgb(236, 239, 244),
+            text: Color::Rgb(216, 222, 233),
+            muted: Color::Rgb(129, 161, 193),
+            accent: Color::Rgb(94, 129, 172),
+            highlight: Color::Rgb(136, 192, 208),
+            ok: Color::Rgb(163, 190, 140),
+            warn: Color::Rgb(235, 203, 139),
+            critical: Color::Rgb(191, 97, 106),
+            banner_text: Color::Rgb(236, 239, 244),
+        }
+    }
+
+    /// Only the 16 ANSI colours, on the terminal's own background.
+    pub fn ansi16() -> Theme {
+        Theme {
+            background: Color::Reset,
+            gauge_background: Color::Black,
+            border: Color::DarkGray,
+            title: Color::White,
+            text: Color::Gray,
+            muted: Color::DarkGray,
+            accent: Color::Magenta,
+            highlight: Color::LightMagenta,
+            ok: Color::Green,
+            warn: Color::Yellow,
+            critical: Color::Red,
+            banner_text: Color::White,
+        }
+    }
+
+    /// One of `PRESETS` by name.
+    pub fn preset(name: &str) -> Option<Theme> {
+        Some(match name {
+            "dark" => Theme::dark(),
+            "light" => Theme::light(),
+            "solarized" => Theme::solarized(),
+            "nord" => Theme::nord(),
+            "16color" => Theme::ansi16(),
+            _ => return None,
+        })
+    }
+
+    /// The dark theme, with the distro's os-release `ANSI_COLOR` as accent when it has one
+    /// that can't be mistaken for the `warn` or `critical` colours, unlike RHEL's red.
+    pub fn for_distro(os_release: Option<&OsRelease>) -> Theme {
+        let dark = Theme::dark();
+        let status: Vec<AnsiColor> = [dark.warn, dark.critical]
+            .iter()
+            .filter_map(|color| ansi_color(*color))
+            .collect();
+        Theme {
+            accent: os_release
+                .and_then(OsRelease::color)
+                .filter(|accent| !status.iter().any(|color| is_close(*accent, *color)))
+                .map(tui_color)
+                .unwrap_or(dark.accent),
+            ..dark
+        }
+    }
+
+    /// The theme with every colour as the terminal can show it.
+    pub fn degrade(self, depth: ColorDepth) -> Theme {
+        let color = |color| degrade(color, depth);
+        Theme {
+            background: color(self.background),
+            gauge_background: color(self.gauge_background),
+            border: color(self.border),
+            title: color(self.title),
+            text: color(self.text),
+            muted: color(self.muted),
+            accent: color(self.accent),
+            highlight: color(self.highlight),
+            ok: color(self.ok),
+            warn: color(self.warn),
+            critical: color(self.critical),
+            banner_text: color(self.banner_text),
+        }
+    }
+
+    pub fn base(&self) -> Style {
+        Style::default().bg(self.background)
+    }
+
+    pub fn text(&self) -> Style {
+        self.base().fg(self.text)
+    }
+
+    pub fn muted(&self) -> Style {
+        self.base().fg(self.muted)
+    }
+
+    pub fn border(&self) -> Style {
+        self.base().fg(self.border)
+    }
+
+    pub fn title(&self) -> Style {
+        self.base().fg(self.title).modifier(Modifier::BOLD)
+    }
+
+    /// The selected tab or table row, reversed when there's no accent colour to show it.
+    pub fn selected(&self) -> Style {
+        if self.accent == Color::Reset {
+            self.title().modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            self.title().bg(self.accent)
+        }
+    }
+
+    pub fn ok(&self) -> Style {
+        self.base().fg(self.ok).modifier(Modifier::BOLD)
+    }
+
+    pub fn warn(&self) -> Style {
+        self.base().fg(self.warn)
+    }
+
+    pub fn critical(&self) -> Style {
+        self.base().fg(self.critical).modifier(Modifier::BOLD)
+    }
+
+    /// A gauge filled with `color`.
+    pub fn gauge(&self, color: Color) -> Style {
+        Style::default()
+            .fg(color)
+            .bg(self.gauge_background)
+            .modifier(Modifier::ITALIC | Modifier::BOLD)
+    }
+
+    /// The boxes for things that need the admin's attention right away, reversed when
+    /// there's no colour to make them stand out.
+    pub fn banner(&self) -> Style {
+        let style = Style::default()
+            .fg(self.banner_text)
+            .bg(self.critical)
+            .modifier(Modifier::BOLD);
+        if self.critical == Color::Reset {
+            style.modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    /// A part of the header greeting, `field` being `None` for the template's own text.
+    pub fn greeting(&self, greeting: &Greeting, field: Option<Field>) -> Style {
+        let style = match field {
+            Some(Field::Name) | Some(Field::User) => {
+                self.base().fg(self.highlight).modifier(Modifier::BOLD)
+            }
+            Some(Field::Host) | Some(Field::Os) => self.title(),
+            _ => self.text(),
+        };
+        match field.and_then(|field| greeting.style(field)) {
+            Some(custom) => {
+                let style = match custom.color {
+                    Some(color) => style.fg(tui_color(color)),
+                    None => style,
+                };
+                if custom.bold {
+                    style.modifier(style.modifier | Modifier::BOLD)
+                } else {
+                    style
+                }
+            }
+            None => style,
+        }
+    }
+
+    /// A bordered box with `title`.
+    pub fn block<'a>(&self, title: &'a str) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.border())
+            .title(title)
+            .title_style(self.title())
+    }
+}
+
+pub fn tui_color(color: AnsiColor) -> Color {
+    match color {
+        AnsiColor::Indexed(index) => Color::Indexed(index),
+        AnsiColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+/// `color` as a palette entry or RGB, `None` for the terminal's default colour.
+pub fn ansi_color(color: Color) -> Option<AnsiColor> {
+    match color {
+        Color::Rgb(r, g, b) => Some(AnsiColor::Rgb(r, g, b)),
+        Color::Indexed(index) => Some(AnsiColor::Indexed(index)),
+        color => ANSI_16
+            .iter()
+            .position(|ansi| *ansi == color)
+            .map(|index| AnsiColor::Indexed(index as u8)),
+    }
+}
+
+/// The 16 ANSI colours by number, as tui names them.
+const ANSI_16: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// `color` as the terminal can show it, `Reset` when it shows no colours. The 16 ANSI
+/// colours keep their names, which terminals may have redefined.
+pub fn degrade(color: Color, depth: ColorDepth) -> Color {
+    let ansi = match color {
+        Color::Rgb(r, g, b) => AnsiColor::Rgb(r, g, b),
+        Color::Indexed(index) => AnsiColor::Indexed(index),
+        _ if depth == ColorDepth::NoColor => return Color::Reset,
+        color => return color,
+    };
+    match depth.apply(ansi) {
+        Some(AnsiColor::Indexed(index)) if depth == ColorDepth::Ansi16 => {
+            ANSI_16[index as usize % 16]
+        }
+        Some(color) => tui_color(color),
+        None => Color::Reset,
+    }
+}
+
+/// `style` with its colours as the terminal can show them.
+pub fn degrade_style(style: Style, depth: ColorDepth) -> Style {
+    style
+        .fg(degrade(style.fg, depth))
+        .bg(degrade(style.bg, depth))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_theme_preset() {
+        for name in PRESETS.iter() {
+            assert!(Theme::preset(name).is_some(), "{}", name);
+        }
+        assert_eq!(Theme::preset("dark"), Some(Theme::default()));
+        assert_eq!(Theme::preset("monokai"), None);
+
+        let arch = OsRelease {
+            ansi_color: "38;2;23;147;209".to_string(),
+            ..OsRelease::default()
+        };
+        let theme = Theme::for_distro(Some(&arch));
+        assert_eq!(theme.accent, Color::Rgb(23, 147, 209));
+        assert_eq!(theme.background, Theme::dark().background);
+        assert_eq!(Theme::for_distro(None), Theme::dark());
+        let rhel = OsRelease {
+            ansi_color: "0;31".to_string(),
+            ..OsRelease::default()
+        };
+        assert_eq!(Theme::for_distro(Some(&rhel)).accent, Theme::dark().accent);
+
+        let dark = Theme::dark();
+        assert_eq!(dark.degrade(ColorDepth::TrueColor), dark);
+        let basic = dark.degrade(ColorDepth::Ansi16);
+        assert_eq!(basic.background, Color::Black);
+        assert_eq!(basic.accent, Color::DarkGray);
+        assert_eq!(basic.critical, Color::Red);
+        assert_eq!(dark.degrade(ColorDepth::Ansi256).accent, Color::Indexed(60));
+        let plain = dark.degrade(ColorDepth::NoColor);
+        assert_eq!((plain.text, plain.critical), (Color::Reset, Color::Reset));
+        let banner = plain.banner();
+        assert_eq!((banner.fg, banner.bg), (Color::Reset, Color::Reset));
+        assert!(banner.modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_greeting_style() {
+        use crate::sys::greeting::FieldStyle;
+
+        let theme = Theme::dark();
+        let mut greeting = Greeting::default();
+        greeting.fields.insert(
+            Field::Host,
+            FieldStyle {
+                color: Some(AnsiColor::Indexed(1)),
+                bold: false,
+            },
+        );
+        assert_eq!(theme.greeting(&greeting, None), theme.text());
+        assert_eq!(
+            theme.greeting(&greeting, Some(Field::Name)).fg,
+            theme.highlight
+        );
+        let host = theme.greeting(&greeting, Some(Field::Host));
+        assert_eq!(host.fg, Color::Indexed(1));
+        assert!(host.modifier.contains(Modifier::BOLD));
+    }
+}